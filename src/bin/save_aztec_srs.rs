@@ -1,6 +1,12 @@
-//! Load Aztec SRS and save to files
+//! Load Aztec SRS and save to files, pass `--compressed` to also write a
+//! copy of each file with compressed points and print the rows of
+//! `AZTEC20_COMPRESSED_CHECKSUMS` pinning them
+
+use sha2::{Digest, Sha256};
 
 fn main() {
+    let compressed = std::env::args().any(|arg| arg == "--compressed");
+
     // instead of perfect power_of_two, we include a few more points
     // because many variant requires slightly more for masking or optimization
     // reasons. You can choose any degree that's slightly larger than your
@@ -9,15 +15,35 @@ fn main() {
         1024, 16_392, 32_776, 65_544, 131_080, 262_152, 524_296, 1_048_584,
     ];
 
-    for degree in degrees {
-        print!("Parsing SRS for degree {degree} ...");
-        let mut srs = ark_srs::kzg10::aztec20::setup_from_raw(degree).unwrap();
-        println!(" done.");
+    // parse the transcripts only once for the largest degree, smaller ones are
+    // prefixes of it.
+    let max_degree = *degrees.iter().max().unwrap();
+    print!("Parsing SRS for degree {max_degree} ...");
+    let mut srs = ark_srs::kzg10::aztec20::setup_from_raw(max_degree).unwrap();
+    println!(" done.");
+
+    let mut rows = Vec::new();
+    for degree in degrees.into_iter().rev() {
         srs.powers_of_g.truncate(degree + 1);
 
         let dest = ark_srs::load::kzg10::bn254::aztec::default_path(None, degree).unwrap();
         print!("Saving to {} ...", dest.display());
-        ark_srs::load::store_data(srs, dest).unwrap();
+        ark_srs::load::store_data(srs.clone(), dest.clone()).unwrap();
         println!(" done.");
+
+        if compressed {
+            let compressed_dest =
+                ark_srs::load::kzg10::bn254::aztec::compressed_path(None, degree).unwrap();
+            print!("Saving to {} ...", compressed_dest.display());
+            ark_srs::load::kzg10::bn254::aztec::compress_aztec_srs(dest, compressed_dest.clone())
+                .unwrap();
+            println!(" done.");
+            let checksum = Sha256::digest(std::fs::read(compressed_dest).unwrap());
+            rows.push(format!("({degree}, hex!(\"{}\")),", hex::encode(checksum)));
+        }
+    }
+
+    for row in rows.iter().rev() {
+        println!("{row}");
     }
 }
@@ -0,0 +1,35 @@
+//! Verify Aztec's raw ignition transcripts under `data/aztec20`, or the
+//! directory given as the first argument
+
+fn main() {
+    let dir = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "./data/aztec20".to_string());
+
+    println!("Verifying transcripts in {dir} ...");
+    let report = ark_srs::verify::aztec20::verify_transcripts(&dir).unwrap();
+    for t in &report.transcripts {
+        let status = if t.issues.is_empty() { "ok" } else { "FAILED" };
+        println!(
+            "transcript{:02}.dat: G1 points [{}, {}) {status}",
+            t.manifest.transcript_number,
+            t.manifest.start_from,
+            t.manifest.start_from + t.manifest.num_g1_points,
+        );
+        for issue in &t.issues {
+            println!("  - {issue}");
+        }
+    }
+    println!(
+        "{} of {} transcripts found, {} G1 points verified.",
+        report.transcripts.len(),
+        report
+            .transcripts
+            .first()
+            .map_or(0, |t| t.manifest.total_transcripts),
+        report.num_verified_g1_points,
+    );
+    if !report.is_valid() {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,561 @@
+//! Resolution of pre-serialized parameter files of a [`Ceremony`] from the
+//! local cache, so that any degree is served by the smallest verified file
+//! covering it, without a new entry in its pinned checksums (e.g.
+//! [`AZTEC20_CHECKSUMS`](crate::constants::AZTEC20_CHECKSUMS)).
+//!
+//! A cached file is a *candidate* once it has a [`ChunkIndex`], which is only
+//! written after the file passed its checks, and the sha256sum in the index is
+//! listed: either by the release manifest, or by the local manifest listing
+//! the trimmed copies of verified files written by [`CacheResolver::trim()`].
+//! With [`TrustPolicy::Custom`], indexed files of unpinned degrees count too.
+//!
+//! Candidates are checked again when loaded, as the index and the local
+//! manifest are as writable as the files. With [`TrustPolicy::Pinned`],
+//! released files are checked against the release, and locally derived files
+//! are only served if their header names the released file they're taken
+//! from, which is cached, and their points are a prefix of it. Other locally
+//! derived files (e.g. parsed from transcripts) are only served with
+//! [`TrustPolicy::Custom`].
+
+use alloc::{format, string::ToString, vec::Vec};
+use ark_poly_commit::kzg10::UniversalParams;
+use ark_std::marker::PhantomData;
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ceremony::Ceremony,
+    config::{Decompression, SrsConfig, TrustPolicy},
+    download::Downloader,
+    errors::{Error, Result},
+    load::{
+        encode_srs,
+        kzg10::{load_params_prefix_with, load_params_with},
+        ChunkIndex, PointEncoding, SrsHeader,
+    },
+    lock::CacheLock,
+    manifest::{Artifact, ArtifactManifest},
+};
+
+const LOCAL_MANIFEST: &str = "local-manifest.json";
+const LOCAL_VERSION: &str = "local";
+
+/// A verified parameter file in the cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedFile {
+    /// max degree supported by the file
+    pub degree: usize,
+    /// where the file is
+    pub path: PathBuf,
+    /// sha256sum of the file
+    pub sha256: [u8; 32],
+}
+
+/// Finds the cached parameter files of `C` in a directory, see the
+/// [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct CacheResolver<C: Ceremony> {
+    dir: PathBuf,
+    release: Vec<(usize, [u8; 32])>,
+    chunk_roots: Vec<([u8; 32], [u8; 32])>,
+    local: ArtifactManifest,
+    trust: TrustPolicy,
+    decompression: Decompression,
+    _ceremony: PhantomData<C>,
+}
+
+impl<C: Ceremony> CacheResolver<C> {
+    /// Filename of the local manifest in the cache directory
+    pub const LOCAL_MANIFEST: &'static str = LOCAL_MANIFEST;
+
+    /// Resolver over the files in `dir`, pinned by `release` and the local
+    /// manifest of `dir`, if any.
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        release: &ArtifactManifest,
+        trust: TrustPolicy,
+    ) -> Result<Self> {
+        let dir = dir.into();
+        let local = read_local_manifest(&dir.join(Self::LOCAL_MANIFEST))?;
+        Ok(Self {
+            dir,
+            release: release.checksums(C::NAME),
+            chunk_roots: release.chunk_roots(C::NAME),
+            local,
+            trust,
+            decompression: Decompression::default(),
+            _ceremony: PhantomData,
+        })
+    }
+
+    /// How points of loaded files are checked, see [`Decompression`]
+    pub fn decompression(mut self, decompression: Decompression) -> Self {
+        self.decompression = decompression;
+        self
+    }
+
+    /// Resolver over the cache configured in `cfg`, pinned by the manifest of
+    /// its release.
+    pub fn from_config(cfg: &SrsConfig) -> Result<Self> {
+        Self::from_manifest(cfg, &ArtifactManifest::resolve(cfg)?)
+    }
+
+    // `from_config()` with the release manifest already resolved
+    pub(crate) fn from_manifest(cfg: &SrsConfig, release: &ArtifactManifest) -> Result<Self> {
+        let mut dir = cfg.cache_root()?;
+        dir.push(C::NAME);
+        Ok(Self::new(dir, release, cfg.trust)?.decompression(cfg.decompression))
+    }
+
+    /// The cache directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// `(degree, sha256sum)` of the files pinned when loaded, only the
+    /// released ones: the local manifest is as writable as the cache
+    pub fn checksums(&self) -> Vec<(usize, [u8; 32])> {
+        self.release.clone()
+    }
+
+    /// `(sha256sum, chunk index root)` of the released files pinning their
+    /// [`ChunkIndex`]
+    pub fn chunk_roots(&self) -> &[([u8; 32], [u8; 32])] {
+        &self.chunk_roots
+    }
+
+    /// All candidate files of the cache, by increasing degree
+    pub fn scan(&self) -> Result<Vec<CachedFile>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::file(&self.dir, e)),
+        };
+        let mut checksums = self.release.clone();
+        checksums.extend(self.local.checksums(C::NAME));
+        let mut files = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| Error::file(&self.dir, e))?.path();
+            if path.extension().is_none_or(|ext| ext != "bin") {
+                continue;
+            }
+            match self.check(&path, &checksums) {
+                Ok(Some(file)) => files.push(file),
+                Ok(None) => {},
+                Err(e) => tracing::debug!("Skipping {}: {e}", path.display()),
+            }
+        }
+        files.sort_by_key(|f| f.degree);
+        Ok(files)
+    }
+
+    // The cached file at `path` if a candidate
+    fn check(&self, path: &Path, checksums: &[(usize, [u8; 32])]) -> Result<Option<CachedFile>> {
+        let index = match ChunkIndex::read(&ChunkIndex::sidecar_path(path)) {
+            Ok(index) => index,
+            Err(_) => return Ok(None),
+        };
+        let mut f = File::open(path).map_err(|e| Error::file(path, e))?;
+        if f.metadata().map_err(|e| Error::file(path, e))?.len() != index.file_size {
+            return Ok(None);
+        }
+        // the degree from the header, or the length prefix of legacy files
+        let degree = match SrsHeader::read(path)? {
+            Some(header) if header.ceremony != C::NAME => return Ok(None),
+            Some(header) => header.degree,
+            None => {
+                let mut len = [0u8; 8];
+                f.read_exact(&mut len).map_err(|e| Error::file(path, e))?;
+                let Some(degree) = (u64::from_le_bytes(len) as usize).checked_sub(1) else {
+                    return Ok(None);
+                };
+                degree
+            },
+        };
+        let mut pinned = checksums.iter().filter(|(d, _)| *d == degree).peekable();
+        let verified = match pinned.peek() {
+            None => self.trust == TrustPolicy::Custom,
+            Some(_) => pinned.any(|(_, c)| *c == index.file_checksum),
+        };
+        Ok(verified.then(|| CachedFile {
+            degree,
+            path: path.to_path_buf(),
+            sha256: index.file_checksum,
+        }))
+    }
+
+    /// The smallest candidate file covering `degree`
+    pub fn resolve(&self, degree: usize) -> Result<Option<CachedFile>> {
+        Ok(self.scan()?.into_iter().find(|f| f.degree >= degree))
+    }
+
+    /// Write the first `degree + 1` powers of `file` into a new
+    /// self-describing file of the cache, pinned in the local manifest, and
+    /// return it. The header records the sha256sum of the released file
+    /// `file` is taken from as its source, or of `file` itself.
+    pub fn trim(&mut self, file: &CachedFile, degree: usize) -> Result<CachedFile> {
+        self.trim_with(file, degree).map(|(trimmed, _)| trimmed)
+    }
+
+    // `trim()`, also returning the parameters written
+    fn trim_with(
+        &mut self,
+        file: &CachedFile,
+        degree: usize,
+    ) -> Result<(CachedFile, UniversalParams<C::Pairing>)> {
+        if degree > file.degree {
+            return Err(Error::DegreeOutOfRange {
+                degree,
+                max: file.degree,
+            });
+        }
+        let pp = self.load_file(degree, file, true)?;
+        let source = match self.released_source(&file.path)? {
+            Some(source) if self.is_local(file) => source,
+            _ => file.sha256,
+        };
+        let trimmed = self.insert(&pp, [source])?;
+        tracing::info!(
+            "Trimmed {} into {}",
+            file.path.display(),
+            trimmed.path.display()
+        );
+        Ok((trimmed, pp))
+    }
+
+    // Whether `file` is only listed by the local manifest
+    fn is_local(&self, file: &CachedFile) -> bool {
+        !self.release.iter().any(|(_, c)| *c == file.sha256)
+    }
+
+    // The sha256sum of the released file the file at `path` is taken from,
+    // as recorded in its header
+    fn released_source(&self, path: &Path) -> Result<Option<[u8; 32]>> {
+        let Some(header) = SrsHeader::read(path)? else {
+            return Ok(None);
+        };
+        Ok(self
+            .release
+            .iter()
+            .map(|(_, c)| *c)
+            .find(|c| header.source_hashes.contains(&hex::encode(c))))
+    }
+
+    // Load the first `degree + 1` powers of `file`, indexing it if `cached`.
+    // A file only listed by the local manifest is checked against the released
+    // file it's taken from with `TrustPolicy::Pinned` (see `load_local()`), or
+    // as any unpinned file with `TrustPolicy::Custom`.
+    fn load_file(
+        &self,
+        degree: usize,
+        file: &CachedFile,
+        cached: bool,
+    ) -> Result<UniversalParams<C::Pairing>> {
+        let (checksums, chunk_roots) = match (self.trust, self.is_local(file)) {
+            (_, false) => (&self.release[..], &self.chunk_roots[..]),
+            (TrustPolicy::Pinned, true) => return self.load_local(degree, file, cached),
+            (TrustPolicy::Custom, true) => (&[][..], &[][..]),
+        };
+        load_params_prefix_with::<C>(
+            degree,
+            file.path.clone(),
+            checksums,
+            chunk_roots,
+            self.trust,
+            self.decompression,
+            cached,
+        )
+    }
+
+    // `load_file()` of a locally derived `file`, which is only trusted if it
+    // matches the local manifest, and its points are a prefix of those of the
+    // cached released file named in its header. Fails with
+    // `Error::UntrustedFile` otherwise.
+    fn load_local(
+        &self,
+        degree: usize,
+        file: &CachedFile,
+        cached: bool,
+    ) -> Result<UniversalParams<C::Pairing>> {
+        let untrusted = || Error::UntrustedFile {
+            path: file.path.clone(),
+        };
+        let source = self.released_source(&file.path)?.ok_or_else(untrusted)?;
+        let source = self
+            .scan()?
+            .into_iter()
+            .find(|f| f.sha256 == source && f.degree >= degree)
+            .ok_or_else(untrusted)?;
+
+        let pp = load_params_with::<C>(
+            degree,
+            file.path.clone(),
+            &[(file.degree, file.sha256)],
+            TrustPolicy::Pinned,
+            self.decompression,
+            cached,
+        )?;
+        let expected = load_params_prefix_with::<C>(
+            degree,
+            source.path,
+            &self.release,
+            &self.chunk_roots,
+            TrustPolicy::Pinned,
+            self.decompression,
+            cached,
+        )?;
+        if pp != expected {
+            tracing::error!(
+                "{} doesn't match the released file it's taken from",
+                file.path.display()
+            );
+            return Err(untrusted());
+        }
+        Ok(pp)
+    }
+
+    // Load the first `degree + 1` powers of `path`, a file of the directory of
+    // the resolver supplied by the caller, thus never indexed
+    pub(crate) fn load_path(
+        &self,
+        degree: usize,
+        path: PathBuf,
+    ) -> Result<UniversalParams<C::Pairing>> {
+        let local = self
+            .local
+            .artifacts
+            .iter()
+            .find(|a| a.ceremony == C::NAME && path.file_name() == Some(a.name.as_ref()));
+        match local {
+            Some(artifact) => {
+                let file = CachedFile {
+                    degree: artifact.degree,
+                    path,
+                    sha256: artifact.sha256,
+                };
+                self.load_file(degree, &file, false)
+            },
+            None => load_params_prefix_with::<C>(
+                degree,
+                path,
+                &self.checksums(),
+                &self.chunk_roots,
+                self.trust,
+                self.decompression,
+                false,
+            ),
+        }
+    }
+
+    /// Write `pp` into a new self-describing file of the cache with
+    /// uncompressed points, named by [`Ceremony::local_name()`] and pinned in
+    /// the local manifest, and return it. The
+    /// header records `source_hashes`, the digests of the files `pp` is taken
+    /// from.
+    pub fn insert<T: AsRef<[u8]>>(
+        &mut self,
+        pp: &UniversalParams<C::Pairing>,
+        source_hashes: impl IntoIterator<Item = T>,
+    ) -> Result<CachedFile> {
+        let header = SrsHeader::kzg10(C::CURVE, C::NAME, PointEncoding::Uncompressed)
+            .source_hashes(source_hashes);
+        let bytes = encode_srs(pp, header)?;
+        let degree = pp.powers_of_g.len().saturating_sub(1);
+        let file = CachedFile {
+            degree,
+            path: self.dir.join(C::local_name(degree)),
+            sha256: Sha256::digest(&bytes).into(),
+        };
+
+        // pin the file before writing it, so that it's never unpinned
+        fs::create_dir_all(&self.dir).map_err(|e| Error::file(&self.dir, e))?;
+        self.pin(&file)?;
+        {
+            let _lock = CacheLock::exclusive(&file.path)?;
+            let temp = Downloader::temp_path(&file.path);
+            fs::write(&temp, &bytes).map_err(|e| Error::file(&temp, e))?;
+            fs::rename(&temp, &file.path).map_err(|e| Error::file(&file.path, e))?;
+            ChunkIndex::build(&bytes, ChunkIndex::DEFAULT_CHUNK_SIZE)
+                .write(&ChunkIndex::sidecar_path(&file.path))?;
+        }
+        Ok(file)
+    }
+
+    // Add `file` to the local manifest, merging with concurrent writers
+    fn pin(&mut self, file: &CachedFile) -> Result<()> {
+        let path = self.dir.join(Self::LOCAL_MANIFEST);
+        let _lock = CacheLock::exclusive(&path)?;
+        let mut local = read_local_manifest(&path)?;
+        local
+            .artifacts
+            .retain(|a| a.ceremony != C::NAME || a.degree != file.degree);
+        local.artifacts.push(Artifact {
+            ceremony: C::NAME.to_string(),
+            degree: file.degree,
+            name: C::local_name(file.degree),
+            sha256: file.sha256,
+            encoding: PointEncoding::Uncompressed,
+            chunk_root: None,
+        });
+        let temp = Downloader::temp_path(&path);
+        fs::write(&temp, local.to_json()).map_err(|e| Error::file(&temp, e))?;
+        fs::rename(&temp, &path).map_err(|e| Error::file(&path, e))?;
+        self.local = local;
+        Ok(())
+    }
+
+    /// Load the smallest candidate file covering `degree` passing its checks,
+    /// trimming it first if `trim` and it's larger than needed. Returns `None`
+    /// if no such file is cached.
+    pub fn load(
+        &mut self,
+        degree: usize,
+        trim: bool,
+    ) -> Result<Option<UniversalParams<C::Pairing>>> {
+        let mut untrusted = Vec::new();
+        loop {
+            let Some(file) = self
+                .scan()?
+                .into_iter()
+                .find(|f| f.degree >= degree && !untrusted.contains(&f.path))
+            else {
+                return Ok(None);
+            };
+            tracing::info!("Using cached {}", file.path.display());
+            let res = if trim && file.degree > degree {
+                self.trim_with(&file, degree).map(|(_, pp)| pp)
+            } else {
+                self.load_file(degree, &file, true)
+            };
+            match res {
+                // locally derived files failing their checks are skipped, or
+                // were quarantined if modified since pinned
+                Err(Error::UntrustedFile { path } | Error::ChecksumMismatch { path })
+                    if self.trust == TrustPolicy::Pinned && self.is_local(&file) =>
+                {
+                    tracing::warn!("Skipping untrusted {}", path.display());
+                    untrusted.push(file.path);
+                },
+                res => return res.map(Some),
+            }
+        }
+    }
+}
+
+fn read_local_manifest(path: &Path) -> Result<ArtifactManifest> {
+    match fs::read(path) {
+        Ok(bytes) => {
+            let manifest =
+                ArtifactManifest::from_json(&bytes).map_err(|e| e.context(path.display()))?;
+            if manifest.version != LOCAL_VERSION {
+                return Err(Error::MalformedCache(format!(
+                    "{} is not a local manifest",
+                    path.display()
+                )));
+            }
+            Ok(manifest)
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ArtifactManifest {
+            version: LOCAL_VERSION.to_string(),
+            artifacts: Vec::new(),
+        }),
+        Err(e) => Err(Error::file(path, e)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ceremony::setup_helper, kzg10::aztec20::Aztec20,
+        load::kzg10::bn254::aztec::load_aztec_srs_prefix,
+    };
+
+    #[test]
+    fn test_cache_resolver() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let src = dir.path().join("kzg10-aztec20-srs-1024.bin");
+        fs::copy("data/aztec20/kzg10-aztec20-srs-1024.bin", &src)?;
+        let release = ArtifactManifest::builtin();
+        let mut resolver =
+            CacheResolver::<Aztec20>::new(dir.path(), &release, TrustPolicy::Pinned)?;
+
+        // only files verified once in the cache are picked up
+        assert_eq!(resolver.resolve(100)?, None);
+        let pp = load_aztec_srs_prefix(1024, src.clone())?;
+        assert_eq!(resolver.resolve(100)?, None);
+        load_params_prefix_with::<Aztec20>(
+            0,
+            src.clone(),
+            &resolver.checksums(),
+            resolver.chunk_roots(),
+            TrustPolicy::Pinned,
+            Decompression::Unchecked,
+            true,
+        )?;
+        let file = resolver.resolve(100)?.unwrap();
+        assert_eq!((file.degree, &file.path), (1024, &src));
+        assert_eq!(resolver.resolve(1025)?, None);
+
+        // trimmed copies are pinned locally, and preferred from then on
+        let trimmed = resolver.trim(&file, 100)?;
+        assert_eq!(trimmed.degree, 100);
+        let header = SrsHeader::read(&trimmed.path)?.unwrap();
+        assert_eq!(header.source_hashes, [hex::encode(file.sha256)]);
+        let mut resolver =
+            CacheResolver::<Aztec20>::new(dir.path(), &release, TrustPolicy::Pinned)?;
+        assert_eq!(resolver.resolve(50)?, Some(trimmed.clone()));
+        assert_eq!(resolver.resolve(101)?, Some(file.clone()));
+        let loaded = resolver.load(50, false)?.unwrap();
+        assert_eq!(loaded.powers_of_g, pp.powers_of_g[..51]);
+
+        // copies of a released degree never take the name of the released file
+        let copy = resolver.trim(&file, 1024)?;
+        assert_eq!(
+            copy.path,
+            dir.path().join("kzg10-aztec20-srs-1024-local.bin")
+        );
+        assert_eq!(load_aztec_srs_prefix(1024, src.clone())?, pp);
+        // and are pinned by the local manifest next to them when used directly
+        let cfg = SrsConfig::default().offline(true);
+        assert_eq!(
+            setup_helper::<Aztec20>(1024, Some(copy.path.clone()), &cfg)?,
+            pp
+        );
+        assert!(copy.path.exists());
+
+        // a forged file, indexed and listed in the local manifest, isn't served
+        // as it's not a prefix of its source, unless trusted
+        let mut forged = pp.clone();
+        forged.powers_of_g.truncate(51);
+        forged.powers_of_g.swap(1, 2);
+        let forged_file = resolver.insert(&forged, [file.sha256])?;
+        assert_eq!(resolver.resolve(50)?, Some(forged_file.clone()));
+        assert_eq!(
+            resolver.load(50, false)?.unwrap().powers_of_g,
+            pp.powers_of_g[..51]
+        );
+        assert!(matches!(
+            setup_helper::<Aztec20>(50, Some(forged_file.path.clone()), &cfg),
+            Err(Error::UntrustedFile { .. })
+        ));
+        let mut custom = CacheResolver::<Aztec20>::new(dir.path(), &release, TrustPolicy::Custom)?;
+        assert_eq!(custom.load(50, false)?.unwrap(), forged);
+        // so are files not taken from a released one
+        let unsourced = resolver.insert(&pp, [[0u8; 32]])?;
+        assert!(matches!(
+            setup_helper::<Aztec20>(1024, Some(unsourced.path.clone()), &cfg),
+            Err(Error::UntrustedFile { .. })
+        ));
+        fs::remove_file(&forged_file.path)?;
+
+        // modified files are no longer verified
+        fs::write(&trimmed.path, b"not an srs")?;
+        assert_eq!(resolver.resolve(50)?.map(|f| f.degree), Some(1024));
+        Ok(())
+    }
+}
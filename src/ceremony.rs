@@ -0,0 +1,437 @@
+//! Ceremonies plugging into the cache, checksum, download and trim plumbing
+//! shared by all pre-serialized KZG10 parameter files, whatever their curve.
+//!
+//! A ceremony supplies its curve, the artifacts of the builtin release and a
+//! parser of its raw transcripts, see [`Ceremony`]. [`setup_with_config()`]
+//! then serves any degree from the smallest verified file in the cache,
+//! downloading the released artifact covering it if needed.
+
+use ark_ec::pairing::Pairing;
+use ark_poly_commit::kzg10::UniversalParams;
+use ark_std::{any::type_name, format, string::String, vec, vec::Vec};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    cache::CacheResolver,
+    config::{SrsConfig, TrustPolicy, VerificationLevel},
+    errors::{Error, Result},
+    load::{
+        kzg10::{artifact_path, load_params_prefix_with},
+        CurveId, PointEncoding,
+    },
+    manifest::{Artifact, ArtifactManifest},
+    verify::kzg10::verify_powers,
+};
+
+/// A trusted setup ceremony whose KZG10 parameters are pre-serialized and
+/// released as artifacts, e.g. [`Aztec20`](crate::kzg10::aztec20::Aztec20).
+pub trait Ceremony {
+    /// Curve of the parameters
+    type Pairing: Pairing;
+
+    /// Name of the ceremony, as listed in release manifests, recorded in the
+    /// header of cached files and used as the cache subdirectory
+    const NAME: &'static str;
+    /// Id of the curve of [`Ceremony::Pairing`]
+    const CURVE: CurveId;
+    /// Max degree supported by the ceremony
+    const MAX_DEGREE: usize;
+
+    /// The artifacts of [`RELEASE_VERSION`](crate::constants::RELEASE_VERSION),
+    /// with their pinned sha256sum
+    fn artifacts() -> Vec<Artifact>;
+
+    /// `(degree, sha256sum)` of the files pinned when loaded from a path, by
+    /// default those of [`Ceremony::artifacts()`]
+    fn checksums() -> Vec<(usize, [u8; 32])> {
+        Self::artifacts()
+            .iter()
+            .map(|a| (a.degree, a.sha256))
+            .collect()
+    }
+
+    /// `(sha256sum, chunk index root)` of the files whose
+    /// [`ChunkIndex`](crate::load::ChunkIndex) is pinned when loaded from a
+    /// path, by default those of [`Ceremony::artifacts()`]
+    fn chunk_roots() -> Vec<([u8; 32], [u8; 32])> {
+        Self::artifacts()
+            .iter()
+            .filter_map(|a| a.chunk_root.map(|root| (a.sha256, root)))
+            .collect()
+    }
+
+    /// Filename of the artifact of `degree` with points in `encoding`
+    fn artifact_name(degree: usize, encoding: PointEncoding) -> String {
+        match encoding {
+            PointEncoding::Uncompressed => format!("kzg10-{}-srs-{degree}.bin", Self::NAME),
+            PointEncoding::Compressed => {
+                format!("kzg10-{}-srs-{degree}-compressed.bin", Self::NAME)
+            },
+        }
+    }
+
+    /// Filename of a file of `degree` derived locally (e.g. a trimmed copy),
+    /// distinct from [`Ceremony::artifact_name()`] so that it's never taken for
+    /// a released artifact
+    fn local_name(degree: usize) -> String {
+        format!("kzg10-{}-srs-{degree}-local.bin", Self::NAME)
+    }
+
+    /// Parse parameters supporting `supported_degree` from the original
+    /// transcripts of the ceremony, located as configured in `cfg`
+    fn setup_from_raw(
+        supported_degree: usize,
+        cfg: &SrsConfig,
+    ) -> Result<UniversalParams<Self::Pairing>>;
+}
+
+/// Retreive public parameters of `C` supporting `supported_degree`, from the
+/// cache configured in `cfg`, downloading the smallest released artifact
+/// covering it if no cached file does.
+pub fn setup_with_config<C: Ceremony>(
+    supported_degree: usize,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<C::Pairing>> {
+    setup_helper::<C>(supported_degree, None, cfg)
+}
+
+// `setup_with_config()`, with `param_file` overriding the cache: either a
+// parameter file (downloaded if missing) or a cache directory.
+pub(crate) fn setup_helper<C: Ceremony>(
+    supported_degree: usize,
+    param_file: Option<PathBuf>,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<C::Pairing>> {
+    if !(1..=C::MAX_DEGREE).contains(&supported_degree) {
+        return Err(Error::DegreeOutOfRange {
+            degree: supported_degree,
+            max: C::MAX_DEGREE,
+        });
+    }
+    let manifest = ArtifactManifest::resolve(cfg)?;
+    let pp = match load_helper::<C>(supported_degree, param_file.clone(), &manifest, cfg) {
+        // quarantined, fetched again unless offline
+        Err(Error::ChecksumMismatch { path }) if !path.exists() && !cfg.is_offline() => {
+            tracing::warn!("{} was quarantined, fetching it again", path.display());
+            load_helper::<C>(supported_degree, param_file, &manifest, cfg)?
+        },
+        res => res?,
+    };
+    if cfg.verification == VerificationLevel::Full {
+        verify_powers(&pp)?;
+    }
+    Ok(pp)
+}
+
+// `setup_helper()` once the degree is checked and the manifest resolved
+fn load_helper<C: Ceremony>(
+    supported_degree: usize,
+    param_file: Option<PathBuf>,
+    manifest: &ArtifactManifest,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<C::Pairing>> {
+    Ok(match param_file {
+        // a cache directory, served by its smallest verified file
+        Some(dir) if dir.is_dir() => {
+            tracing::info!("Looking up SRS files in {dir:?}");
+            CacheResolver::<C>::new(&dir, manifest, cfg.trust)?
+                .decompression(cfg.decompression)
+                .load(supported_degree, cfg.trim_cached)?
+                .ok_or_else(|| {
+                    let hint = format!(
+                        "no verified parameter file of degree >= {supported_degree}, \
+                         load one with `load_params()` first"
+                    );
+                    Error::not_cached(vec![dir], hint)
+                })?
+        },
+        Some(path) => {
+            if !path.exists() {
+                tracing::info!("SRS file {path:?} does not exist");
+                if cfg.is_offline() {
+                    return Err(Error::not_cached(
+                        vec![path],
+                        "the parameter file path must point to an existing file",
+                    ));
+                }
+                let basename = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map(String::from)
+                    .ok_or_else(|| {
+                        Error::MalformedCache(format!("Invalid SRS file path {}", path.display()))
+                    })?;
+                cfg.downloader
+                    .fetch_from_mirrors(&cfg.release_urls(&basename), &path, None)?;
+            }
+            // including the files derived locally next to it
+            let dir = path.parent().unwrap_or_else(|| Path::new("."));
+            CacheResolver::<C>::new(dir, manifest, cfg.trust)?
+                .decompression(cfg.decompression)
+                .load_path(supported_degree, path)?
+        },
+        None => {
+            let mut resolver = CacheResolver::<C>::from_manifest(cfg, manifest)?;
+            match resolver.load(supported_degree, cfg.trim_cached)? {
+                Some(pp) => pp,
+                None => {
+                    let path = fetch_artifact::<C>(supported_degree, manifest, cfg)?;
+                    load_params_prefix_with::<C>(
+                        supported_degree,
+                        path,
+                        &resolver.checksums(),
+                        resolver.chunk_roots(),
+                        cfg.trust,
+                        cfg.decompression,
+                        true,
+                    )?
+                },
+            }
+        },
+    })
+}
+
+// Path of the released parameter file of `C` covering `supported_degree`,
+// downloaded from the mirrors unless already cached.
+fn fetch_artifact<C: Ceremony>(
+    supported_degree: usize,
+    manifest: &ArtifactManifest,
+    cfg: &SrsConfig,
+) -> Result<PathBuf> {
+    // By default, we pre-serialized a few common degrees but may not be *exactly*
+    // `supported_degree` requested, thus attempts to download the corresponding
+    // parameter files will fail. Thus, we try to find the next higher one than
+    // requested instead.
+    let artifact = manifest
+        .artifacts
+        .iter()
+        .filter(|a| {
+            a.ceremony == C::NAME && a.encoding == cfg.encoding && a.degree >= supported_degree
+        })
+        .min_by_key(|a| a.degree)
+        .ok_or_else(|| Error::UnsupportedDegree {
+            degree: supported_degree,
+            supported: manifest
+                .artifacts
+                .iter()
+                .filter(|a| a.ceremony == C::NAME && a.encoding == cfg.encoding)
+                .map(|a| a.degree)
+                .collect(),
+        })?;
+    tracing::info!(
+        "Requested degree {supported_degree} using next higher available degree {}",
+        artifact.degree
+    );
+    let param_file = artifact_path::<C>(cfg.cache_dir.clone(), artifact.degree, artifact.encoding)?;
+
+    // Download SRS file if it doesn't exist
+    if param_file.exists() {
+        tracing::info!("SRS file already exists.");
+        return Ok(param_file);
+    }
+    tracing::info!("SRS file {param_file:?} does not exist");
+    let urls = cfg.release_urls(&artifact.name);
+    if cfg.is_offline() {
+        let hint = format!(
+            "run `ark_srs::ceremony::prefetch_with_config::<{}>(&[{supported_degree}], ..)` \
+             with network access, or download {} to it",
+            type_name::<C>(),
+            urls.first().map_or(artifact.name.as_str(), String::as_str)
+        );
+        return Err(Error::not_cached(vec![param_file], hint));
+    }
+    cfg.downloader
+        .fetch_from_mirrors(&urls, &param_file, Some(&artifact.sha256))?;
+    Ok(param_file)
+}
+
+/// Fill the cache configured in `cfg` with the parameter files of `C` used by
+/// [`setup_with_config()`] for each of `degrees`, e.g. in a build step ahead of
+/// running offline, see [`SrsConfig::offline()`].
+///
+/// Files already cached are checked against their pinned checksum instead of
+/// being downloaded again. Returns the path of the file for each degree.
+pub fn prefetch_with_config<C: Ceremony>(
+    degrees: &[usize],
+    cfg: &SrsConfig,
+) -> Result<Vec<PathBuf>> {
+    let manifest = ArtifactManifest::resolve(cfg)?;
+    let checksums = manifest.checksums(C::NAME);
+    let chunk_roots = manifest.chunk_roots(C::NAME);
+    let mut checked = Vec::<PathBuf>::new();
+    degrees
+        .iter()
+        .map(|&degree| {
+            if !(1..=C::MAX_DEGREE).contains(&degree) {
+                return Err(Error::DegreeOutOfRange {
+                    degree,
+                    max: C::MAX_DEGREE,
+                });
+            }
+            let param_file = fetch_artifact::<C>(degree, &manifest, cfg)?;
+            if !checked.contains(&param_file) {
+                // verifies the checksum and builds the chunk index if missing
+                load_params_prefix_with::<C>(
+                    0,
+                    param_file.clone(),
+                    &checksums,
+                    &chunk_roots,
+                    TrustPolicy::Pinned,
+                    cfg.decompression,
+                    true,
+                )?;
+                checked.push(param_file.clone());
+            }
+            Ok(param_file)
+        })
+        .collect()
+}
+
+/// Parse public parameters of `C` supporting `supported_degree` from its
+/// original transcripts, see [`Ceremony::setup_from_raw()`], checking them as
+/// configured in `cfg`.
+pub fn setup_from_raw_with_config<C: Ceremony>(
+    supported_degree: usize,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<C::Pairing>> {
+    if !(1..=C::MAX_DEGREE).contains(&supported_degree) {
+        return Err(Error::DegreeOutOfRange {
+            degree: supported_degree,
+            max: C::MAX_DEGREE,
+        });
+    }
+    let pp = C::setup_from_raw(supported_degree, cfg)?;
+    if cfg.verification == VerificationLevel::Full {
+        verify_powers(&pp)?;
+    }
+    Ok(pp)
+}
+
+/// Parse public parameters of `C` as [`setup_from_raw_with_config()`] does,
+/// and store them in the cache configured in `cfg`, pinned in its local
+/// manifest, so that [`setup_with_config()`] serves them from then on without
+/// any released artifact. The header of the cached file records
+/// `source_hashes`, the digests of the transcripts parsed.
+///
+/// # Note
+/// The cached file isn't taken from a released file, thus is only served with
+/// [`TrustPolicy::Custom`], see [`CacheResolver`].
+///
+/// Returns the path of the cached file.
+pub fn cache_from_raw_with_config<C: Ceremony, T: AsRef<[u8]>>(
+    supported_degree: usize,
+    cfg: &SrsConfig,
+    source_hashes: impl IntoIterator<Item = T>,
+) -> Result<PathBuf> {
+    let pp = setup_from_raw_with_config::<C>(supported_degree, cfg)?;
+    let file = CacheResolver::<C>::from_config(cfg)?.insert(&pp, source_hashes)?;
+    Ok(file.path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::load::{encode_srs, ChunkIndex, SrsHeader};
+    use anyhow::Result;
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly_commit::kzg10::KZG10;
+    use ark_std::{string::ToString, test_rng};
+    use sha2::{Digest, Sha256};
+
+    // A ceremony over BLS12-381 only known from the manifest of its release
+    #[derive(Debug, Clone, Copy)]
+    struct MockCeremony;
+
+    impl Ceremony for MockCeremony {
+        type Pairing = Bls12_381;
+
+        const NAME: &'static str = "mock";
+        const CURVE: CurveId = CurveId::Bls12_381;
+        const MAX_DEGREE: usize = 256;
+
+        fn artifacts() -> Vec<Artifact> {
+            Vec::new()
+        }
+
+        fn setup_from_raw(
+            _supported_degree: usize,
+            _cfg: &SrsConfig,
+        ) -> crate::Result<UniversalParams<Bls12_381>> {
+            Err(Error::MalformedTranscript("no transcript".into()))
+        }
+    }
+
+    #[test]
+    fn test_generic_ceremony() -> Result<()> {
+        let pp = KZG10::<Bls12_381, DensePolynomial<Fr>>::setup(128, true, &mut test_rng())?;
+        let bytes = encode_srs(
+            &pp,
+            SrsHeader::kzg10(
+                CurveId::Bls12_381,
+                MockCeremony::NAME,
+                PointEncoding::Compressed,
+            ),
+        )?;
+
+        let mirror = tempfile::tempdir()?;
+        let release = mirror.path().join("v9.9.9");
+        std::fs::create_dir_all(&release)?;
+        let name = MockCeremony::artifact_name(128, PointEncoding::Compressed);
+        std::fs::write(release.join(&name), &bytes)?;
+        let index = ChunkIndex::build(&bytes, ChunkIndex::DEFAULT_CHUNK_SIZE);
+        let manifest = ArtifactManifest {
+            version: "9.9.9".to_string(),
+            artifacts: vec![Artifact {
+                ceremony: MockCeremony::NAME.to_string(),
+                degree: 128,
+                name,
+                sha256: Sha256::digest(&bytes).into(),
+                encoding: PointEncoding::Compressed,
+                chunk_root: Some(index.root),
+            }],
+        };
+        std::fs::write(release.join(ArtifactManifest::FILENAME), manifest.to_json())?;
+
+        let cache = tempfile::tempdir()?;
+        let cfg = SrsConfig::default()
+            .cache_dir(cache.path())
+            .mirrors([format!("file://{}", mirror.path().display())])
+            .release_version("9.9.9")
+            .encoding(PointEncoding::Compressed)
+            .verification(VerificationLevel::Full);
+        let paths = prefetch_with_config::<MockCeremony>(&[100], &cfg)?;
+        assert_eq!(paths[0].parent(), Some(cache.path().join("mock").as_path()));
+        // indexed in the cache, with the root pinned by the manifest
+        assert_eq!(
+            ChunkIndex::read(&ChunkIndex::sidecar_path(&paths[0]))?,
+            index
+        );
+
+        // served from the cache, all parameters beyond the G1 powers included
+        let cfg = cfg.offline(true).trim_cached(true);
+        let loaded = setup_with_config::<MockCeremony>(100, &cfg)?;
+        assert_eq!(loaded.powers_of_g, pp.powers_of_g[..101]);
+        assert_eq!(loaded.powers_of_gamma_g, pp.powers_of_gamma_g);
+        assert_eq!(loaded.neg_powers_of_h, pp.neg_powers_of_h);
+        assert_eq!((loaded.h, loaded.beta_h), (pp.h, pp.beta_h));
+        let trimmed = cache
+            .path()
+            .join(MockCeremony::NAME)
+            .join(MockCeremony::local_name(100));
+        assert!(trimmed.exists());
+        assert_eq!(
+            setup_with_config::<MockCeremony>(50, &cfg)?
+                .powers_of_g
+                .len(),
+            51
+        );
+
+        assert!(matches!(
+            setup_with_config::<MockCeremony>(257, &cfg),
+            Err(Error::DegreeOutOfRange { max: 256, .. })
+        ));
+        assert!(setup_from_raw_with_config::<MockCeremony>(100, &cfg).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+//! Configuration of where and how SRS files are fetched, cached and checked,
+//! so that a single process can work with several locations without relying
+//! on process-global environment variables.
+
+use ark_serialize::Validate;
+use ark_std::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    ceremony::Ceremony,
+    constants::{DATA_DIR, OFFLINE_ENV, RELEASE_BASE_URL, RELEASE_VERSION},
+    download::Downloader,
+    errors::Result,
+    load::{get_project_root, PointEncoding},
+};
+
+/// How much a loaded SRS is checked before being returned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VerificationLevel {
+    /// Only check the pinned sha256sum of pre-serialized files
+    #[default]
+    Checksum,
+    /// Check the pinned sha256sum and that the points are successive powers
+    /// of the same secret, see [`verify_powers()`](crate::verify::kzg10::verify_powers)
+    Full,
+}
+
+/// Which pre-serialized parameter files are accepted on loading
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrustPolicy {
+    /// Only files matching the pinned sha256sum of their degree
+    #[default]
+    Pinned,
+    /// Also files of degrees without a pinned sha256sum, e.g. made with
+    /// `save-aztec-srs` at non-standard degrees. Instead, all their points are
+    /// checked to be valid subgroup elements on first load. Files of pinned
+    /// degrees still have to match.
+    Custom,
+}
+
+/// How points are checked when decoding pre-serialized parameter files.
+///
+/// Compressed points are always recovered on the curve, as only their x
+/// coordinate is stored, but checking they are in the prime subgroup (and that
+/// uncompressed points are on the curve) costs a scalar multiplication each.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Decompression {
+    /// Skip the subgroup check, files are already trusted by their pinned
+    /// sha256sum
+    #[default]
+    Unchecked,
+    /// Check every decoded point is a valid subgroup element
+    Checked,
+}
+
+impl From<Decompression> for Validate {
+    fn from(decompression: Decompression) -> Self {
+        match decompression {
+            Decompression::Unchecked => Validate::No,
+            Decompression::Checked => Validate::Yes,
+        }
+    }
+}
+
+/// Configuration of [`aztec20::setup_with_config()`](crate::kzg10::aztec20::setup_with_config),
+/// built from its `Default` with chained setters:
+///
+/// ```no_run
+/// use ark_srs::{SrsConfig, VerificationLevel};
+///
+/// let cfg = SrsConfig::default()
+///     .cache_dir("/tmp/ark-srs")
+///     .offline(true)
+///     .verification(VerificationLevel::Full);
+/// let pp = ark_srs::kzg10::aztec20::setup_with_config(1024, &cfg).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SrsConfig {
+    pub(crate) cache_dir: Option<PathBuf>,
+    pub(crate) transcript_dir: Option<PathBuf>,
+    pub(crate) mirrors: Vec<String>,
+    pub(crate) downloader: Downloader,
+    pub(crate) release_version: String,
+    pub(crate) offline: bool,
+    pub(crate) verification: VerificationLevel,
+    pub(crate) trust: TrustPolicy,
+    pub(crate) trim_cached: bool,
+    pub(crate) encoding: PointEncoding,
+    pub(crate) decompression: Decompression,
+}
+
+impl Default for SrsConfig {
+    fn default() -> Self {
+        Self {
+            cache_dir: None,
+            transcript_dir: None,
+            mirrors: vec![RELEASE_BASE_URL.to_string()],
+            downloader: Downloader::default(),
+            release_version: RELEASE_VERSION.to_string(),
+            offline: false,
+            verification: VerificationLevel::default(),
+            trust: TrustPolicy::default(),
+            trim_cached: false,
+            encoding: PointEncoding::default(),
+            decompression: Decompression::default(),
+        }
+    }
+}
+
+impl SrsConfig {
+    /// Root directory of pre-serialized parameter files, defaults to the
+    /// platform data directory of `ark-srs`
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Directory of the original ceremony transcripts, defaults to
+    /// `./data/{ceremony}`, e.g. `./data/aztec20`
+    pub fn transcript_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.transcript_dir = Some(dir.into());
+        self
+    }
+
+    /// Base URLs of released assets, tried in order until a download
+    /// succeeds. Each hosts releases at `{url}/v{version}/`, either over
+    /// `http(s)://` or as a local `file://` directory. Defaults to the GitHub
+    /// releases of `ark-srs`.
+    pub fn mirrors<S: Into<String>>(mut self, urls: impl IntoIterator<Item = S>) -> Self {
+        self.mirrors = urls.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Append a mirror, tried after the ones already configured
+    pub fn add_mirror(mut self, url: impl Into<String>) -> Self {
+        self.mirrors.push(url.into());
+        self
+    }
+
+    /// Retries and progress reporting of downloads, see [`Downloader`]
+    pub fn downloader(mut self, downloader: Downloader) -> Self {
+        self.downloader = downloader;
+        self
+    }
+
+    /// Version of the release to download parameter files from, its
+    /// artifacts are listed in its [`ArtifactManifest`](crate::manifest::ArtifactManifest)
+    pub fn release_version(mut self, version: impl Into<String>) -> Self {
+        self.release_version = version.into();
+        self
+    }
+
+    /// Never download, fail with [`Error::NotCached`](crate::Error::NotCached)
+    /// if a file is not cached. Setting the `ARK_SRS_OFFLINE` environment
+    /// variable enables it regardless of this setting, so that hermetic
+    /// builds can't touch the network.
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// How much loaded SRS are checked, see [`VerificationLevel`]
+    pub fn verification(mut self, level: VerificationLevel) -> Self {
+        self.verification = level;
+        self
+    }
+
+    /// Which parameter files are accepted, see [`TrustPolicy`]
+    pub fn trust(mut self, policy: TrustPolicy) -> Self {
+        self.trust = policy;
+        self
+    }
+
+    /// When a degree is served by a larger cached file, also write a trimmed
+    /// copy of it for next time, see [`CacheResolver`](crate::cache::CacheResolver)
+    pub fn trim_cached(mut self, trim: bool) -> Self {
+        self.trim_cached = trim;
+        self
+    }
+
+    /// Encoding of the released parameter files to download, compressed ones
+    /// are half the size but slower to load. Files of either encoding already
+    /// cached are used regardless.
+    pub fn encoding(mut self, encoding: PointEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// How points of loaded parameter files are checked, see [`Decompression`]
+    pub fn decompression(mut self, decompression: Decompression) -> Self {
+        self.decompression = decompression;
+        self
+    }
+
+    /// URLs of the released asset `basename` on each mirror, in order
+    pub fn release_urls(&self, basename: &str) -> Vec<String> {
+        self.mirrors
+            .iter()
+            .map(|url| {
+                format!(
+                    "{}/v{}/{basename}",
+                    url.trim_end_matches('/'),
+                    self.release_version
+                )
+            })
+            .collect()
+    }
+
+    /// Whether downloads are disabled, by [`SrsConfig::offline()`] or by the
+    /// `ARK_SRS_OFFLINE` environment variable
+    pub fn is_offline(&self) -> bool {
+        self.offline || offline_from_env()
+    }
+
+    /// Root directory of the cache, see [`SrsConfig::cache_dir()`]
+    pub fn cache_root(&self) -> Result<PathBuf> {
+        match &self.cache_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => get_project_root(),
+        }
+    }
+
+    /// Directory of the transcripts of `C`, see [`SrsConfig::transcript_dir()`]
+    pub fn transcript_root<C: Ceremony>(&self) -> PathBuf {
+        self.transcript_dir
+            .clone()
+            .unwrap_or_else(|| Path::new(DATA_DIR).join(C::NAME))
+    }
+}
+
+// Whether `ARK_SRS_OFFLINE` is set to anything but empty, `0` or `false`
+pub(crate) fn offline_from_env() -> bool {
+    std::env::var(OFFLINE_ENV)
+        .map(|v| !matches!(v.trim().to_lowercase().as_str(), "" | "0" | "false"))
+        .unwrap_or(false)
+}
@@ -2,9 +2,20 @@
 
 use hex_literal::hex;
 
-/// data related to Aztec's ignition ceremony (including original transcripts
-/// from the ceremony and the arkworks serialized data blobs)
-pub(crate) const AZTEC20_DIR: &str = "./data/aztec20";
+/// data related to each ceremony (including original transcripts from the
+/// ceremony and the arkworks serialized data blobs), under a subdirectory named
+/// after it, e.g. `./data/aztec20` for Aztec's ignition ceremony
+pub(crate) const DATA_DIR: &str = "./data";
+
+/// Environment variable enabling the offline mode when set to anything but
+/// empty, `0` or `false`, see [`SrsConfig::offline()`](crate::SrsConfig::offline)
+pub const OFFLINE_ENV: &str = "ARK_SRS_OFFLINE";
+
+/// Base URL of the releases hosting pre-serialized parameter files
+pub const RELEASE_BASE_URL: &str = "https://github.com/EspressoSystems/ark-srs/releases/download";
+
+/// Release hosting the parameter files listed in [`AZTEC20_CHECKSUMS`]
+pub const RELEASE_VERSION: &str = "0.2.0";
 
 /// List of pre-computed arkworks-serialized parameter files, storing their
 /// `(degree, sha256sum)`
@@ -42,3 +53,200 @@ pub const AZTEC20_CHECKSUMS: [(usize, [u8; 32]); 8] = [
         hex!("cded83e82e4b49fee4cb2e0f374f996954fe12548ad39100432ee493069ef09d"),
     ),
 ];
+
+/// Roots of the [`ChunkIndex`](crate::load::ChunkIndex) of the files of
+/// [`AZTEC20_CHECKSUMS`] over chunks of
+/// [`ChunkIndex::DEFAULT_CHUNK_SIZE`](crate::load::ChunkIndex::DEFAULT_CHUNK_SIZE),
+/// storing their `(sha256sum, root)`, so that a prefix of a file is verified
+/// without hashing it whole. Only the degrees shipped with the crate are
+/// pinned: the root of a file of a single chunk is its sha256sum.
+pub const AZTEC20_CHUNK_ROOTS: [([u8; 32], [u8; 32]); 1] = [(
+    hex!("0e2a5fb1d9102ee5b06723472b23f4f29f938712251a7b5b75eed4df4049871c"),
+    hex!("0e2a5fb1d9102ee5b06723472b23f4f29f938712251a7b5b75eed4df4049871c"),
+)];
+
+/// List of pre-computed parameter files with compressed points, written by
+/// [`compress_aztec_srs()`](crate::load::kzg10::bn254::aztec::compress_aztec_srs)
+/// from the files of [`AZTEC20_CHECKSUMS`], storing their `(degree, sha256sum)`.
+/// `save-aztec-srs --compressed` writes them for every released degree and
+/// prints their rows; only the degrees whose source file ships with the crate
+/// are pinned. Not hosted in [`RELEASE_VERSION`]: reproduce them locally.
+pub const AZTEC20_COMPRESSED_CHECKSUMS: [(usize, [u8; 32]); 1] = [(
+    1024,
+    hex!("cfaaa0cd7907afefcd73be17160d315506b8760faafe4e2904e397d3a9068843"),
+)];
+
+/// Roots of the [`ChunkIndex`](crate::load::ChunkIndex) of the files of
+/// [`AZTEC20_COMPRESSED_CHECKSUMS`], as [`AZTEC20_CHUNK_ROOTS`] does
+pub const AZTEC20_COMPRESSED_CHUNK_ROOTS: [([u8; 32], [u8; 32]); 1] = [(
+    hex!("cfaaa0cd7907afefcd73be17160d315506b8760faafe4e2904e397d3a9068843"),
+    hex!("cfaaa0cd7907afefcd73be17160d315506b8760faafe4e2904e397d3a9068843"),
+)];
+
+/// List of pre-computed arkworks-serialized parameter files from Zcash's Powers
+/// of Tau ceremony, storing their `(degree, sha256sum)`. None is released yet.
+pub const ZCASH_POWERSOFTAU_CHECKSUMS: [(usize, [u8; 32]); 0] = [];
+
+/// BLAKE2b hashes of the last `response` (or `challenge`) files of Zcash's
+/// Powers of Tau ceremony, as published by its coordinator, accepted by
+/// [`ZcashPowersOfTau::setup_from_raw()`](crate::kzg10::zcash_powersoftau::ZcashPowersOfTau)
+/// under [`TrustPolicy::Pinned`](crate::TrustPolicy::Pinned). None is pinned
+/// yet: check the chain of files with
+/// [`verify_chain()`](crate::kzg10::zcash_powersoftau::verify_chain) and pass
+/// its result to
+/// [`setup_from_transcript()`](crate::kzg10::zcash_powersoftau::setup_from_transcript).
+pub const ZCASH_POWERSOFTAU_TRANSCRIPT_HASHES: [[u8; 64]; 0] = [];
+
+/// List of pre-computed arkworks-serialized parameter files from Aleo's
+/// universal setup, storing their `(degree, sha256sum)`. None is released yet,
+/// the files published with snarkVM are pinned by [`ALEO_FILE_CHECKSUMS`].
+pub const ALEO_CHECKSUMS: [(usize, [u8; 32]); 0] = [];
+
+/// sha256sums of the files of Aleo's universal setup as published with snarkVM
+/// (see the `.metadata` files of `snarkvm-parameters`), by filename
+pub const ALEO_FILE_CHECKSUMS: [(&str, [u8; 32]); 31] = [
+    (
+        "beta-h.usrs",
+        hex!("fd0a8538d4ad4f60694b5e81dec6687d8672480ea6b878205eef0c1bd51dc3bc"),
+    ),
+    (
+        "powers-of-beta-gamma.usrs",
+        hex!("03fd7fc81234b014e1e260c797e9b716b5587429871026f17a455cd0938d8be1"),
+    ),
+    (
+        "neg-powers-of-beta.usrs",
+        hex!("ded57ae81c510f8fd50c8f3ec3387e8397ffdde71acfcc639ff1a2728a0848cd"),
+    ),
+    (
+        "powers-of-beta-15.usrs",
+        hex!("eb7040c25ce1a19a5a9a1d12046c5820ba9c2768c606290f8a80fa2bd0e1e5ed"),
+    ),
+    (
+        "powers-of-beta-16.usrs",
+        hex!("84631bc11e1a6db99db085a8de586014e7dd10e97b42cbd766c105dea014bbd1"),
+    ),
+    (
+        "powers-of-beta-17.usrs",
+        hex!("7c27308fddb11be496e4dd43ee4ae2adb31051b715bb7c0c48656a9be6fec676"),
+    ),
+    (
+        "powers-of-beta-18.usrs",
+        hex!("7a12bcb6de982aa3010dde16a7be73ec399a46717b37ea189112ef14f4c88cca"),
+    ),
+    (
+        "powers-of-beta-19.usrs",
+        hex!("e535d44614ef8e667ac39132cad61516dc4dcbce62d74c396b670b21c9b4e16a"),
+    ),
+    (
+        "powers-of-beta-20.usrs",
+        hex!("3daad5ed5b5b7108b46bf1d0639a0d6e06deee4c784730ec9672e8cf1184e923"),
+    ),
+    (
+        "powers-of-beta-21.usrs",
+        hex!("9bd37fcfe64998a205dab4a5cfc6d84d0d97b497a07321139a97f1dc9ba78c59"),
+    ),
+    (
+        "powers-of-beta-22.usrs",
+        hex!("5f79ba342c66ee2a0fd031c6259e5bf7104573db49d3931542e31272a72493d6"),
+    ),
+    (
+        "powers-of-beta-23.usrs",
+        hex!("f5fd3208a5e9462bbc73f42bf143c7ec4e0488488749208de3657ad6c621239b"),
+    ),
+    (
+        "powers-of-beta-24.usrs",
+        hex!("b71278e7e47c1e17495fef946f3a7c4aa0dfa5a1670813e74df47b62a0426340"),
+    ),
+    (
+        "powers-of-beta-25.usrs",
+        hex!("8baf0683cc80f154da0e4432845bfb61bc8ccc8c36823596537f6f1925aec3d4"),
+    ),
+    (
+        "powers-of-beta-26.usrs",
+        hex!("68aa40bae5e78589e53bb0a629c08086c462a04c7cdb2ba2856597847e19bcaf"),
+    ),
+    (
+        "powers-of-beta-27.usrs",
+        hex!("87e9800ace5ec3d6da9cdc9609765e03d63ee404ee94c56ea13341ac0b864936"),
+    ),
+    (
+        "powers-of-beta-28.usrs",
+        hex!("168e5f5bf030517c4485b2461d0a9e5064126a9bb7d7fe85eb287d75cedd376f"),
+    ),
+    (
+        "shifted-powers-of-beta-15.usrs",
+        hex!("7c732bfabee7ac5e4496ccd0db1ee11127adb453b3b6014e6230cb51aba7ed49"),
+    ),
+    (
+        "shifted-powers-of-beta-16.usrs",
+        hex!("d99bcb30361df496b60f2714305fee65068d199d22cafad3bde09e715e47d2b5"),
+    ),
+    (
+        "shifted-powers-of-beta-17.usrs",
+        hex!("20251789535c798606a205d04dea8a3a46a2c79e4e7f450e48a99285112bef6c"),
+    ),
+    (
+        "shifted-powers-of-beta-18.usrs",
+        hex!("9a1859e01968284239e3ca3e33246a6a1af35aee1ccc82990ff5af4c3b50cac4"),
+    ),
+    (
+        "shifted-powers-of-beta-19.usrs",
+        hex!("662e3437feba9da7d1206f4cfa9297c2835124a639c9f6c5ce3e0da81f55658c"),
+    ),
+    (
+        "shifted-powers-of-beta-20.usrs",
+        hex!("dbb509d0889c61f1b5f6e1cc3b3e8b7f9c9d4f7741efedcb3b76cf462d94e698"),
+    ),
+    (
+        "shifted-powers-of-beta-21.usrs",
+        hex!("1561ed19528561e43feec789e0d3d550e2900c4d75a54a1b985988fc97e4fc31"),
+    ),
+    (
+        "shifted-powers-of-beta-22.usrs",
+        hex!("45ee74981ca848cac90893bd75ffad8f7c5b698ac6eb4ce9fbc250cd8e30eb62"),
+    ),
+    (
+        "shifted-powers-of-beta-23.usrs",
+        hex!("6f07282af6850da13448ea27b5e3ade144e38f209cc6f97525a1a91dc928615c"),
+    ),
+    (
+        "shifted-powers-of-beta-24.usrs",
+        hex!("504f938c9f9a11816bd5448f05bac37a82f21f737b0101b79a13506b2f99be37"),
+    ),
+    (
+        "shifted-powers-of-beta-25.usrs",
+        hex!("d0208eea5b3313d910597a1591d8cc9d4c68f77d4d11a3742486ed2d7221e393"),
+    ),
+    (
+        "shifted-powers-of-beta-26.usrs",
+        hex!("38e70ce8857b11f46cd41b68caf7716528688f81b4c6d20a2f20c7342f934258"),
+    ),
+    (
+        "shifted-powers-of-beta-27.usrs",
+        hex!("d0a5c5fec29ab729cbc216ff870b9c7c188574d2e1f0515e1c098e45da7e62b3"),
+    ),
+    (
+        "shifted-powers-of-beta-28.usrs",
+        hex!("17b89a564030f02c6a8ce47280ff50822924b305a5d7bc4a9371797003fb79a4"),
+    ),
+];
+
+/// List of Hermez's `powersOfTau28_hez_final_{power}.ptau` files whose BLAKE2b
+/// hash, as published in the snarkjs README, has been checked, storing their
+/// `(power, blake2b)`
+pub const PPOT_BLAKE2B_CHECKSUMS: [(u32, [u8; 64]); 1] = [(
+    8,
+    hex!("d6a8fb3a04feb600096c3b791f936a578c4e664d262e4aa24beed1b7a9a96aa5eb72864d628db247e9293384b74b36ffb52ca8d148d6e1b8b51e279fdf57b583"),
+)];
+
+/// URL of Ethereum's EIP-4844 KZG ceremony output, as published by a tagged
+/// release of `c-kzg-4844` (with the G1 points in both forms)
+pub const ETH4844_URL: &str =
+    "https://raw.githubusercontent.com/ethereum/c-kzg-4844/v2.1.0/src/trusted_setup.txt";
+
+/// List of accepted `trusted_setup.txt` files from the EIP-4844 ceremony,
+/// storing their `(num_g1_points, sha256sum)`
+pub const ETH4844_CHECKSUMS: [(usize, [u8; 32]); 1] = [(
+    4096,
+    hex!("d39b9f2d047cc9dca2de58f264b6a09448ccd34db967881a6713eacacf0f26b7"),
+)];
@@ -0,0 +1,518 @@
+//! Streaming downloads of (possibly large) parameter files: data is written to
+//! disk and hashed as it arrives, partial downloads are resumed with HTTP range
+//! requests, and failed attempts are retried with exponential backoff.
+
+use alloc::{borrow::ToOwned, boxed::Box, format, string::String, sync::Arc, vec};
+use core::{fmt, time::Duration};
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    errors::{Error, Result},
+    lock::CacheLock,
+};
+
+// data is streamed through a buffer of 64 KB
+const BUF_SIZE: usize = 1 << 16;
+
+/// Receiver of download progress, all methods default to doing nothing.
+pub trait DownloadProgress: Send + Sync {
+    /// An attempt to download `url` starts, with `offset` bytes already on
+    /// disk from previous attempts, out of `total` bytes if known
+    fn on_start(&self, _url: &str, _offset: u64, _total: Option<u64>) {}
+
+    /// `downloaded` bytes out of `total` bytes (if known) are on disk
+    fn on_progress(&self, _downloaded: u64, _total: Option<u64>) {}
+
+    /// The download of `url` completed with `size` bytes
+    fn on_finish(&self, _url: &str, _size: u64) {}
+}
+
+/// Ignores all progress
+impl DownloadProgress for () {}
+
+/// Downloads files over `http(s)://`, or copies them from `file://` URLs.
+///
+/// Data is streamed into `<dest>.temp`, which is only renamed to `dest` once
+/// complete (and matching its checksum, if given), so that `dest` is never in
+/// an incomplete state. If an attempt fails, e.g. the connection drops, the
+/// next one resumes from the end of `<dest>.temp`, which is also the case if a
+/// previous process was interrupted.
+///
+/// Downloads hold the [`CacheLock`] of `dest`, so that concurrent processes
+/// never write the same `<dest>.temp`.
+#[derive(Clone)]
+pub struct Downloader {
+    max_retries: u32,
+    backoff: Duration,
+    progress: Arc<dyn DownloadProgress>,
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            backoff: Duration::from_secs(1),
+            progress: Arc::new(()),
+        }
+    }
+}
+
+impl fmt::Debug for Downloader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Downloader")
+            .field("max_retries", &self.max_retries)
+            .field("backoff", &self.backoff)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Downloader {
+    /// Number of retries after a transient failure (network error, 5xx or
+    /// 429 response), defaults to 3
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the first retry, doubled for every following one,
+    /// defaults to 1 second
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Report progress to `progress`
+    pub fn progress(mut self, progress: impl DownloadProgress + 'static) -> Self {
+        self.progress = Arc::new(progress);
+        self
+    }
+
+    /// Path of the partial download of `dest`
+    pub fn temp_path(dest: &Path) -> PathBuf {
+        let mut path = dest.as_os_str().to_owned();
+        path.push(".temp");
+        PathBuf::from(path)
+    }
+
+    /// Download `url` into `dest` and returns its sha256sum. If `expected` is
+    /// given and doesn't match, the data is discarded and `dest` is left
+    /// untouched.
+    pub fn download(
+        &self,
+        url: &str,
+        dest: &Path,
+        expected: Option<&[u8; 32]>,
+    ) -> Result<[u8; 32]> {
+        let _lock = CacheLock::exclusive(dest)?;
+        self.download_locked(url, dest, expected)
+    }
+
+    /// Download the same file from each of `urls` in turn, until one succeeds,
+    /// see [`Downloader::download()`]. Returns the error of the last attempt if
+    /// all of them fail.
+    pub fn download_from_mirrors(
+        &self,
+        urls: &[String],
+        dest: &Path,
+        expected: Option<&[u8; 32]>,
+    ) -> Result<[u8; 32]> {
+        let _lock = CacheLock::exclusive(dest)?;
+        self.download_from_mirrors_locked(urls, dest, expected)
+    }
+
+    /// Same as [`Downloader::download_from_mirrors()`], unless `dest` exists
+    /// once its lock is acquired, e.g. downloaded meanwhile by another
+    /// process, in which case it's reused as is.
+    pub fn fetch_from_mirrors(
+        &self,
+        urls: &[String],
+        dest: &Path,
+        expected: Option<&[u8; 32]>,
+    ) -> Result<()> {
+        let _lock = CacheLock::exclusive(dest)?;
+        if dest.exists() {
+            tracing::info!("{} was downloaded by another process", dest.display());
+            return Ok(());
+        }
+        self.download_from_mirrors_locked(urls, dest, expected)?;
+        Ok(())
+    }
+
+    fn download_from_mirrors_locked(
+        &self,
+        urls: &[String],
+        dest: &Path,
+        expected: Option<&[u8; 32]>,
+    ) -> Result<[u8; 32]> {
+        let mut last_err = Error::NoMirror;
+        for url in urls {
+            match self.download_locked(url, dest, expected) {
+                Ok(checksum) => return Ok(checksum),
+                Err(e) => {
+                    tracing::warn!("Failed to download from {url}: {e}");
+                    last_err = e;
+                },
+            }
+        }
+        Err(last_err)
+    }
+
+    // `download()` while holding the lock of `dest`
+    fn download_locked(
+        &self,
+        url: &str,
+        dest: &Path,
+        expected: Option<&[u8; 32]>,
+    ) -> Result<[u8; 32]> {
+        let span = tracing::info_span!("download", %url, dest = %dest.display());
+        let _guard = span.enter();
+
+        let temp = Self::temp_path(dest);
+        let mut attempt = 0;
+        let (checksum, size) = loop {
+            match self.try_download(url, &temp) {
+                Ok(res) => break res,
+                Err(e) if attempt < self.max_retries && is_transient(&e) => {
+                    let delay = self.backoff.saturating_mul(1 << attempt.min(16));
+                    tracing::warn!("Attempt {} failed: {e}, retrying in {delay:?}", attempt + 1);
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                },
+                Err(e) => return Err(e),
+            }
+        };
+
+        if expected.is_some_and(|c| *c != checksum) {
+            tracing::error!("Checksum failed, discarding {}", temp.display());
+            fs::remove_file(&temp).map_err(|e| Error::file(&temp, e))?;
+            return Err(Error::ChecksumMismatch {
+                path: dest.to_path_buf(),
+            });
+        }
+        fs::rename(&temp, dest).map_err(|e| Error::file(dest, e))?;
+        self.progress.on_finish(url, size);
+        tracing::info!("Saved SRS to {}", dest.display());
+        Ok(checksum)
+    }
+
+    // A single attempt, resuming from the end of `temp`. Returns the sha256sum
+    // and size of the complete file.
+    fn try_download(&self, url: &str, temp: &Path) -> Result<([u8; 32], u64)> {
+        let on_disk = fs::metadata(temp).map_or(0, |m| m.len());
+        let (mut reader, offset, total) = open(url, on_disk)?;
+        if offset > 0 {
+            tracing::info!("Resuming download from {url} at byte {offset}");
+        } else {
+            tracing::info!("Downloading SRS from {url}");
+        }
+
+        let mut f = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(temp)
+            .map_err(|e| Error::file(temp, e))?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; BUF_SIZE];
+        // the kept part of a previous attempt is hashed from disk
+        let mut kept = (&mut f).take(offset);
+        loop {
+            let n = kept.read(&mut buf).map_err(|e| Error::file(temp, e))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        f.set_len(offset).map_err(|e| Error::file(temp, e))?;
+        f.seek(SeekFrom::Start(offset))
+            .map_err(|e| Error::file(temp, e))?;
+
+        self.progress.on_start(url, offset, total);
+        let mut downloaded = offset;
+        loop {
+            let n = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(Error::download(url, e.into())),
+            };
+            f.write_all(&buf[..n]).map_err(|e| Error::file(temp, e))?;
+            hasher.update(&buf[..n]);
+            downloaded += n as u64;
+            self.progress.on_progress(downloaded, total);
+        }
+        f.sync_all().map_err(|e| Error::file(temp, e))?;
+        if total.is_some_and(|t| t != downloaded) {
+            let e = std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!("got {downloaded} bytes out of {total:?}"),
+            );
+            return Err(Error::download(url, e.into()));
+        }
+        Ok((hasher.finalize().into(), downloaded))
+    }
+}
+
+// Open `url` at byte `offset` if supported. Returns the reader, the offset it
+// actually starts from, and the total size if known.
+fn open(url: &str, offset: u64) -> Result<(Box<dyn Read>, u64, Option<u64>)> {
+    if let Some(path) = url.strip_prefix("file://") {
+        let mut f = File::open(path).map_err(|e| Error::file(path, e))?;
+        let total = f.metadata().map_err(|e| Error::file(path, e))?.len();
+        let offset = if offset <= total { offset } else { 0 };
+        f.seek(SeekFrom::Start(offset))
+            .map_err(|e| Error::file(path, e))?;
+        return Ok((Box::new(f), offset, Some(total)));
+    }
+
+    let mut request = ureq::get(url);
+    if offset > 0 {
+        request = request.set("Range", &format!("bytes={offset}-"));
+    }
+    let response = match request.call() {
+        Ok(response) => response,
+        // the partial download is not a prefix of the file, start over
+        Err(ureq::Error::Status(416, _)) if offset > 0 => return open(url, 0),
+        Err(e) => return Err(Error::download(url, e)),
+    };
+    let content_length = response
+        .header("Content-Length")
+        .and_then(|l| l.parse::<u64>().ok());
+    if response.status() == 206 {
+        // Content-Range: bytes {start}-{end}/{total}
+        let range = response.header("Content-Range").unwrap_or_default();
+        let start = range
+            .strip_prefix("bytes ")
+            .and_then(|r| r.split_once('-'))
+            .and_then(|(start, _)| start.parse::<u64>().ok());
+        if start == Some(offset) {
+            let total = range
+                .rsplit_once('/')
+                .and_then(|(_, t)| t.parse::<u64>().ok())
+                .or(content_length.map(|l| offset + l));
+            return Ok((Box::new(response.into_reader()), offset, total));
+        }
+        return open(url, 0);
+    }
+    // range requests not supported, the whole file is sent
+    Ok((Box::new(response.into_reader()), 0, content_length))
+}
+
+// Network errors, truncated responses and server-side errors may go away
+// on retry, other errors (e.g. 404) won't.
+fn is_transient(e: &Error) -> bool {
+    match e {
+        Error::Download { source, .. } => match source.as_ref() {
+            ureq::Error::Status(code, _) => *code >= 500 || *code == 429 || *code == 408,
+            ureq::Error::Transport(_) => true,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use ark_std::rand::RngCore;
+    use std::{
+        io::{BufRead, BufReader},
+        net::{TcpListener, TcpStream},
+        sync::Mutex,
+    };
+
+    /// Local http server, as a stand-in for a release mirror
+    pub(crate) struct TestServer {
+        /// base url
+        pub url: String,
+        /// the byte offset of the `Range` header of each request, if any
+        pub ranges: Arc<Mutex<Vec<Option<u64>>>>,
+    }
+
+    /// Serve the files under `root` until the end of the test process. The
+    /// first `failures` responses are cut after `cut_at` bytes of body.
+    pub(crate) fn serve_dir(root: PathBuf, failures: usize, cut_at: usize) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let log = ranges.clone();
+        std::thread::spawn(move || {
+            for (i, stream) in listener.incoming().flatten().enumerate() {
+                let cut = (i < failures).then_some(cut_at);
+                let _ = serve_file(&root, stream, cut, &log);
+            }
+        });
+        TestServer { url, ranges }
+    }
+
+    // Answer a single GET request, closing the connection afterwards.
+    fn serve_file(
+        root: &Path,
+        mut stream: TcpStream,
+        cut: Option<usize>,
+        log: &Mutex<Vec<Option<u64>>>,
+    ) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request = String::new();
+        reader.read_line(&mut request)?;
+        let mut range = None;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? <= 2 {
+                break;
+            }
+            if let Some(r) = header.to_lowercase().strip_prefix("range: bytes=") {
+                range = r.trim().trim_end_matches('-').parse::<u64>().ok();
+            }
+        }
+        log.lock().unwrap().push(range);
+
+        let path = request.split_whitespace().nth(1).unwrap_or("/");
+        let Ok(body) = fs::read(root.join(path.trim_start_matches('/'))) else {
+            return stream.write_all(
+                b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            );
+        };
+        let (status, start) = match range {
+            Some(start) if start as usize >= body.len() => {
+                return stream.write_all(
+                    b"HTTP/1.1 416 Range Not Satisfiable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                );
+            },
+            Some(start) => (
+                format!(
+                    "206 Partial Content\r\nContent-Range: bytes {start}-{}/{}",
+                    body.len() - 1,
+                    body.len()
+                ),
+                start as usize,
+            ),
+            None => ("200 OK".into(), 0),
+        };
+        let body = &body[start..];
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )?;
+        stream.write_all(&body[..cut.unwrap_or(body.len()).min(body.len())])
+    }
+
+    /// Base url of a local port nobody listens on
+    pub(crate) fn closed_port_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(u64, Option<u64>)>>);
+
+    impl DownloadProgress for Arc<Recorder> {
+        fn on_start(&self, _url: &str, offset: u64, total: Option<u64>) {
+            self.0.lock().unwrap().push((offset, total));
+        }
+    }
+
+    #[test]
+    fn test_resumable_download() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let mut body = vec![0u8; 5 * BUF_SIZE + 123];
+        ark_std::test_rng().fill_bytes(&mut body);
+        fs::write(root.path().join("srs.bin"), &body)?;
+        let checksum: [u8; 32] = Sha256::digest(&body).into();
+
+        // two dropped connections, each resumed where the previous one stopped
+        let server = serve_dir(root.path().to_path_buf(), 2, 100_000);
+        let recorder = Arc::new(Recorder::default());
+        let downloader = Downloader::default()
+            .backoff(Duration::from_millis(1))
+            .progress(recorder.clone());
+        let dir = tempfile::tempdir()?;
+        let dest = dir.path().join("srs.bin");
+        let url = format!("{}/srs.bin", server.url);
+        assert_eq!(downloader.download(&url, &dest, Some(&checksum))?, checksum);
+        assert_eq!(fs::read(&dest)?, body);
+        assert!(!Downloader::temp_path(&dest).exists());
+        assert_eq!(
+            *server.ranges.lock().unwrap(),
+            [None, Some(100_000), Some(200_000)]
+        );
+        let total = Some(body.len() as u64);
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [(0, total), (100_000, total), (200_000, total)]
+        );
+
+        // a partial download left by a previous process is resumed
+        let dest = dir.path().join("resumed.bin");
+        fs::write(Downloader::temp_path(&dest), &body[..1000])?;
+        let server = serve_dir(root.path().to_path_buf(), 0, 0);
+        let url = format!("{}/srs.bin", server.url);
+        assert_eq!(downloader.download(&url, &dest, None)?, checksum);
+        assert_eq!(fs::read(&dest)?, body);
+        assert_eq!(*server.ranges.lock().unwrap(), [Some(1000)]);
+
+        // too many failures, the partial download is kept for next time
+        let dest = dir.path().join("failed.bin");
+        let server = serve_dir(root.path().to_path_buf(), 10, 100);
+        let url = format!("{}/srs.bin", server.url);
+        assert!(downloader
+            .clone()
+            .max_retries(2)
+            .download(&url, &dest, None)
+            .is_err());
+        assert_eq!(fs::read(Downloader::temp_path(&dest))?, body[..300]);
+        // 404 are not retried
+        let url = format!("{}/missing.bin", server.url);
+        assert!(downloader.download(&url, &dest, None).is_err());
+        assert_eq!(server.ranges.lock().unwrap().len(), 4);
+
+        // data not matching the checksum is discarded
+        let dest = dir.path().join("tampered.bin");
+        let url = format!("file://{}", root.path().join("srs.bin").display());
+        assert!(matches!(
+            downloader.download(&url, &dest, Some(&[0u8; 32])),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(!dest.exists() && !Downloader::temp_path(&dest).exists());
+        assert_eq!(downloader.download(&url, &dest, Some(&checksum))?, checksum);
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_fetch() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let mut body = vec![0u8; 3 * BUF_SIZE];
+        ark_std::test_rng().fill_bytes(&mut body);
+        fs::write(root.path().join("srs.bin"), &body)?;
+        let checksum: [u8; 32] = Sha256::digest(&body).into();
+
+        // only one of the concurrent fetches downloads, the others wait for it
+        let server = serve_dir(root.path().to_path_buf(), 0, 0);
+        let urls = [format!("{}/srs.bin", server.url)];
+        let dir = tempfile::tempdir()?;
+        let dest = dir.path().join("cache").join("srs.bin");
+        std::thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    s.spawn(|| {
+                        Downloader::default().fetch_from_mirrors(&urls, &dest, Some(&checksum))
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .try_for_each(|h| h.join().expect("fetch panicked"))
+        })?;
+        assert_eq!(fs::read(&dest)?, body);
+        assert_eq!(server.ranges.lock().unwrap().len(), 1);
+        assert!(CacheLock::lock_path(&dest).exists());
+        Ok(())
+    }
+}
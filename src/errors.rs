@@ -0,0 +1,252 @@
+//! Error types returned by this crate.
+
+use ark_serialize::SerializationError;
+use ark_std::{boxed::Box, format, string::String, vec::Vec};
+use std::path::PathBuf;
+
+/// Errors from fetching, parsing, loading or verifying an SRS.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// The requested degree is not within `[1, max]`.
+    #[error("Max degree has to be between [1, {max}], got {degree}")]
+    DegreeOutOfRange {
+        /// requested degree
+        degree: usize,
+        /// largest degree supported by the ceremony
+        max: usize,
+    },
+    /// No pre-serialized file exists for the requested degree.
+    #[error("No pre-serialized SRS for degree {degree}, supported degrees: {supported:?}")]
+    UnsupportedDegree {
+        /// requested degree
+        degree: usize,
+        /// degrees with a pre-serialized file
+        supported: Vec<usize>,
+    },
+    /// A file doesn't match its pinned checksum.
+    #[error("Checksum failed for {}", path.display())]
+    ChecksumMismatch {
+        /// path of the offending file
+        path: PathBuf,
+    },
+    /// A file has no pinned checksum to check against, and custom files are
+    /// not trusted, see [`TrustPolicy`](crate::TrustPolicy).
+    #[error("No pinned checksum for {}, use TrustPolicy::Custom to accept it", path.display())]
+    UntrustedFile {
+        /// path of the offending file
+        path: PathBuf,
+    },
+    /// Failed to download a file.
+    #[error("Failed to download {url}: {source}")]
+    Download {
+        /// url of the remote file
+        url: String,
+        /// underlying transport or http error
+        source: Box<ureq::Error>,
+    },
+    /// A file or directory is missing or can't be accessed.
+    #[error("{}: {source}", path.display())]
+    File {
+        /// path of the offending file
+        path: PathBuf,
+        /// underlying io error
+        source: std::io::Error,
+    },
+    /// Any other io error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A ceremony transcript (Aztec transcripts, ptau or `trusted_setup.txt`)
+    /// doesn't follow its format.
+    #[error("Malformed transcript: {0}")]
+    MalformedTranscript(String),
+    /// A file produced by this crate (cached SRS, chunk index) is corrupted.
+    #[error("Malformed cache file: {0}")]
+    MalformedCache(String),
+    /// A point is not on the curve, not in the prime subgroup, or its
+    /// coordinates are not reduced.
+    #[error("Invalid point: {0}")]
+    InvalidPoint(String),
+    /// Public parameters are not consistent powers of a single secret.
+    #[error("Invalid SRS: {0}")]
+    InvalidSrs(String),
+    /// (De)serialization with arkworks failed.
+    #[error(transparent)]
+    Serialization(#[from] SerializationError),
+    /// No mirror to download from is configured.
+    #[error("No download mirror configured")]
+    NoMirror,
+    /// A file is not cached and downloads are disabled, see
+    /// [`SrsConfig::offline()`](crate::SrsConfig::offline).
+    #[error(
+        "Not cached in offline mode, searched [{}]; {hint}",
+        display_paths(searched)
+    )]
+    NotCached {
+        /// paths where the file was looked for
+        searched: Vec<PathBuf>,
+        /// how to fill the cache
+        hint: String,
+    },
+    /// No home directory to derive the default cache location from.
+    #[error("Failed to get project root")]
+    NoProjectRoot,
+}
+
+impl Error {
+    pub(crate) fn file(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::File {
+            path: path.into(),
+            source,
+        }
+    }
+
+    // Prefix the message of a format error with where it occurred
+    pub(crate) fn context(self, ctx: impl core::fmt::Display) -> Self {
+        match self {
+            Self::MalformedTranscript(msg) => Self::MalformedTranscript(format!("{ctx}: {msg}")),
+            Self::MalformedCache(msg) => Self::MalformedCache(format!("{ctx}: {msg}")),
+            Self::InvalidPoint(msg) => Self::InvalidPoint(format!("{ctx}: {msg}")),
+            e => e,
+        }
+    }
+
+    pub(crate) fn not_cached(searched: Vec<PathBuf>, hint: impl Into<String>) -> Self {
+        Self::NotCached {
+            searched,
+            hint: hint.into(),
+        }
+    }
+
+    pub(crate) fn download(url: impl Into<String>, source: ureq::Error) -> Self {
+        Self::Download {
+            url: url.into(),
+            source: Box::new(source),
+        }
+    }
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| format!("{}", p.display()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Result type of this crate.
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ceremony::setup_with_config,
+        kzg10::{aztec20::Aztec20, barretenberg::BbCrs, eth4844::TrustedSetup, ppot::Ptau},
+        load::{
+            decode_srs, encode_srs, kzg10::bn254::aztec::load_aztec_srs, store_data, ChunkIndex,
+            CurveId, PointEncoding, SrsHeader,
+        },
+        manifest::AZTEC20,
+        verify::kzg10::verify_powers,
+        SrsConfig,
+    };
+    use ark_bn254::Bn254;
+    use std::{fs, io::Cursor};
+
+    const SRS_1024: &str = "data/aztec20/kzg10-aztec20-srs-1024.bin";
+
+    // the variants callers match on, for each kind of failure
+    #[test]
+    fn test_error_variants() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cfg = SrsConfig::default().cache_dir(dir.path()).offline(true);
+        assert!(matches!(
+            setup_with_config::<Aztec20>(0, &cfg),
+            Err(Error::DegreeOutOfRange { degree: 0, .. })
+        ));
+        assert!(matches!(
+            setup_with_config::<Aztec20>(2_000_000, &cfg),
+            Err(Error::UnsupportedDegree {
+                degree: 2_000_000,
+                ..
+            })
+        ));
+        match setup_with_config::<Aztec20>(1000, &cfg) {
+            Err(Error::NotCached { searched, .. }) => {
+                assert!(searched[0].starts_with(dir.path()))
+            },
+            res => panic!("expect NotCached, got {res:?}"),
+        }
+
+        // missing, tampered and unpinned parameter files
+        let missing = dir.path().join("missing.bin");
+        match load_aztec_srs(1024, missing.clone()) {
+            Err(Error::File { path, .. }) => assert_eq!(path, missing),
+            res => panic!("expect File, got {res:?}"),
+        }
+        let pp = load_aztec_srs(1024, SRS_1024.into())?;
+        let mut bytes = fs::read(SRS_1024)?;
+        bytes[100] ^= 1;
+        let tampered = dir.path().join("tampered.bin");
+        fs::write(&tampered, &bytes)?;
+        match load_aztec_srs(1024, tampered.clone()) {
+            Err(Error::ChecksumMismatch { path }) => assert_eq!(path, tampered),
+            res => panic!("expect ChecksumMismatch, got {res:?}"),
+        }
+        let mut trimmed = pp.clone();
+        trimmed.powers_of_g.truncate(11);
+        let custom = dir.path().join("custom.bin");
+        store_data(trimmed.clone(), custom.clone())?;
+        assert!(matches!(
+            load_aztec_srs(10, custom),
+            Err(Error::UntrustedFile { .. })
+        ));
+
+        // corrupted files written by this crate
+        let mut bytes = encode_srs(
+            &trimmed,
+            SrsHeader::kzg10(CurveId::Bn254, AZTEC20, PointEncoding::Uncompressed),
+        )?;
+        *bytes.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            decode_srs::<Bn254>(&bytes, true),
+            Err(Error::MalformedCache(_))
+        ));
+        let index = dir.path().join("index.chunks");
+        fs::write(&index, b"ARKSRSCI")?;
+        assert!(matches!(
+            ChunkIndex::read(&index),
+            Err(Error::MalformedCache(_))
+        ));
+
+        // malformed transcripts, invalid points and inconsistent powers
+        assert!(matches!(
+            Ptau::read(&mut Cursor::new(b"ptax"), None),
+            Err(Error::MalformedTranscript(_))
+        ));
+        assert!(matches!(
+            TrustedSetup::from_txt(b"4096\n"),
+            Err(Error::MalformedTranscript(_))
+        ));
+        let mut g1 = [0u8; 64];
+        (g1[31], g1[63]) = (1, 1);
+        match BbCrs::read(&mut &g1[..], &mut &[0u8; 128][..], 1) {
+            Err(Error::InvalidPoint(msg)) => assert!(msg.starts_with("bn254_g1.dat")),
+            res => panic!("expect InvalidPoint, got {res:?}"),
+        }
+        let mut swapped = pp;
+        swapped.powers_of_g.swap(1, 2);
+        assert!(matches!(verify_powers(&swapped), Err(Error::InvalidSrs(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_context() {
+        let e = Error::MalformedCache("too short".into()).context("srs.bin");
+        assert!(matches!(&e, Error::MalformedCache(msg) if msg == "srs.bin: too short"));
+        // other variants are left as is
+        let e = Error::NoMirror.context("srs.bin");
+        assert!(matches!(e, Error::NoMirror));
+    }
+}
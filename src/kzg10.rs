@@ -1,3 +1,9 @@
 //! SRS related to [KZG10](https://cacr.uwaterloo.ca/techreports/2010/cacr2010-10.pdf)
 
+pub mod aleo;
 pub mod aztec20;
+pub mod barretenberg;
+pub mod eth4844;
+pub mod halo2;
+pub mod ppot;
+pub mod zcash_powersoftau;
@@ -0,0 +1,717 @@
+//! Aleo's universal setup over BLS12-377, as published with snarkVM: 2^28
+//! powers of beta in G1 split across `powers-of-beta-{power}.usrs` files, and
+//! again from the top power down across `shifted-powers-of-beta-{power}.usrs`
+//! files, along with `beta-h.usrs`, the powers of beta times gamma in G1 used
+//! for hiding commitments, and the negative powers of beta in G2 used for
+//! degree bounds. Each file is an arkworks value with uncompressed points,
+//! pinned by [`ALEO_FILE_CHECKSUMS`].
+//! For concrete details: <https://github.com/ProvableHQ/snarkVM/tree/mainnet/parameters>
+
+use std::path::{Path, PathBuf};
+
+use ark_bls12_377::{Bls12_377, Fq2, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::MontFp;
+use ark_poly_commit::kzg10::UniversalParams;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use ark_std::{
+    cfg_chunks,
+    collections::BTreeMap,
+    format,
+    fs::{self, File},
+    io::{Read, Seek, SeekFrom},
+    ops::Range,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    ceremony::{self, Ceremony},
+    config::SrsConfig,
+    constants::{ALEO_CHECKSUMS, ALEO_FILE_CHECKSUMS},
+    errors::{Error, Result},
+    load::{file_sha256, is_verified, mark_verified, CurveId, PointEncoding},
+    manifest::{Artifact, ALEO},
+    verify::kzg10::verify_powers,
+    TrustPolicy,
+};
+
+/// Power of the first files of G1 powers, holding the first (or, for the
+/// shifted ones, the last) 2^15 powers
+pub const MIN_POWER: u32 = 15;
+/// Power of the last files of G1 powers
+pub const MAX_POWER: u32 = 28;
+/// Number of powers of beta in G1
+pub const NUM_POWERS: usize = 1 << MAX_POWER;
+/// Max degree supported by the setup
+pub const MAX_DEGREE: usize = NUM_POWERS - 1;
+
+// Size of the length prefix of a serialized `Vec` of powers
+const LEN_SIZE: u64 = 8;
+
+/// The generator of G2 of snarkVM, which `beta-h.usrs` and
+/// `neg-powers-of-beta.usrs` are powers of. It differs from the one of
+/// `ark_bls12_377`, while the generator of G1 is the first power of beta.
+pub const H: G2Affine = G2Affine::new_unchecked(
+    Fq2::new(
+        MontFp!("170590608266080109581922461902299092015242589883741236963254737235977648828052995125541529645051927918098146183295"),
+        MontFp!("83407003718128594709087171351153471074446327721872642659202721143408712182996929763094113874399921859453255070254"),
+    ),
+    Fq2::new(
+        MontFp!("1843833842842620867708835993770650838640642469700861403869757682057607397502738488921663703124647238454792872005"),
+        MontFp!("33145532013610981697337930729788870077912093258611421158732879580766461459275194744385880708057348608045241477209"),
+    ),
+);
+
+/// File holding `beta.h`, see [`H`]
+pub const BETA_H_FILE: &str = "beta-h.usrs";
+/// File holding the powers of beta times gamma in G1, as a map from `i` to
+/// `gamma.beta^i.g`
+pub const POWERS_OF_BETA_GAMMA_FILE: &str = "powers-of-beta-gamma.usrs";
+/// File holding negative powers of beta in G2, as a map from the degree bound
+/// `d` to `beta^{-(MAX_DEGREE - d)}.h`, i.e. shifting a polynomial of degree
+/// `d` to the top power of beta
+pub const NEG_POWERS_OF_BETA_FILE: &str = "neg-powers-of-beta.usrs";
+
+/// Filename of the G1 powers of `power`, see [`powers_range()`]
+pub fn powers_basename(power: u32) -> String {
+    format!("powers-of-beta-{power}.usrs")
+}
+
+/// Filename of the shifted G1 powers of `power`, see [`shifted_powers_range()`]
+pub fn shifted_powers_basename(power: u32) -> String {
+    format!("shifted-powers-of-beta-{power}.usrs")
+}
+
+/// Powers of beta held by [`powers_basename(power)`](powers_basename): the
+/// first `2^MIN_POWER` ones, then each next file continues the previous one,
+/// up to `2^power`.
+pub fn powers_range(power: u32) -> Range<usize> {
+    if power == MIN_POWER {
+        0..1 << MIN_POWER
+    } else {
+        1 << (power - 1)..1 << power
+    }
+}
+
+/// Powers of beta held by
+/// [`shifted_powers_basename(power)`](shifted_powers_basename): the last
+/// `2^MIN_POWER` ones, then each next file holds the ones before the previous
+/// one, from `NUM_POWERS - 2^power`.
+pub fn shifted_powers_range(power: u32) -> Range<usize> {
+    if power == MIN_POWER {
+        NUM_POWERS - (1 << MIN_POWER)..NUM_POWERS
+    } else {
+        NUM_POWERS - (1 << power)..NUM_POWERS - (1 << (power - 1))
+    }
+}
+
+/// Check the published file at `path` against its pinned sha256sum in
+/// [`ALEO_FILE_CHECKSUMS`], by filename. The whole file is hashed, once per
+/// process.
+pub fn check_file(path: &Path) -> Result<()> {
+    let name = path.file_name().and_then(|name| name.to_str());
+    let (_, expected) = ALEO_FILE_CHECKSUMS
+        .iter()
+        .find(|(pinned, _)| Some(*pinned) == name)
+        .ok_or_else(|| Error::UntrustedFile {
+            path: path.to_path_buf(),
+        })?;
+    let mut f = File::open(path).map_err(|e| Error::file(path, e))?;
+    let meta = f.metadata().map_err(|e| Error::file(path, e))?;
+    if is_verified(path, &meta) {
+        return Ok(());
+    }
+    if file_sha256(&mut f)? != *expected {
+        return Err(Error::ChecksumMismatch {
+            path: path.to_path_buf(),
+        });
+    }
+    mark_verified(path, &meta);
+    Ok(())
+}
+
+/// Read the first `num_points` powers of beta in G1 from the
+/// `powers-of-beta-{power}.usrs` files under `dir`, see [`powers_range()`].
+/// Only the files needed are read, each checked with [`check_file()`] under
+/// [`TrustPolicy::Pinned`], and every point is checked to be in the prime
+/// order subgroup.
+pub fn read_powers_of_g(
+    dir: impl AsRef<Path>,
+    num_points: usize,
+    trust: TrustPolicy,
+) -> Result<Vec<G1Affine>> {
+    let mut powers = Vec::with_capacity(num_points);
+    for power in MIN_POWER..=MAX_POWER {
+        if powers.len() == num_points {
+            break;
+        }
+        let path = dir.as_ref().join(powers_basename(power));
+        // the powers end with the last file present
+        if power > MIN_POWER && !path.exists() {
+            break;
+        }
+        let range = powers_range(power);
+        let count = range.len().min(num_points - powers.len());
+        let points = read_points(&path, range, count, false, trust)?;
+        let end = points.len() < count;
+        powers.extend(points);
+        // or with a file holding fewer powers than its range
+        if end {
+            break;
+        }
+    }
+    if powers.len() < num_points {
+        return Err(Error::DegreeOutOfRange {
+            degree: num_points.saturating_sub(1),
+            max: powers.len().saturating_sub(1),
+        });
+    }
+    Ok(powers)
+}
+
+/// Read the last `num_points` powers of beta in G1, i.e. from
+/// `NUM_POWERS - num_points` on, from the `shifted-powers-of-beta-{power}.usrs`
+/// files under `dir`, see [`shifted_powers_range()`] and
+/// [`read_powers_of_g()`].
+///
+/// They have no room in [`UniversalParams`]: snarkVM commits to polynomials
+/// shifted by them to enforce degree bounds, opened against
+/// [`UniversalParams::neg_powers_of_h`].
+pub fn read_shifted_powers_of_g(
+    dir: impl AsRef<Path>,
+    num_points: usize,
+    trust: TrustPolicy,
+) -> Result<Vec<G1Affine>> {
+    if num_points > NUM_POWERS {
+        return Err(Error::DegreeOutOfRange {
+            degree: num_points - 1,
+            max: MAX_DEGREE,
+        });
+    }
+    let mut chunks = Vec::new();
+    let mut remaining = num_points;
+    for power in MIN_POWER..=MAX_POWER {
+        if remaining == 0 {
+            break;
+        }
+        let path = dir.as_ref().join(shifted_powers_basename(power));
+        let range = shifted_powers_range(power);
+        let count = range.len().min(remaining);
+        // the highest powers of the file, next to those already read
+        chunks.push(read_points(&path, range, count, true, trust)?);
+        remaining -= count;
+    }
+    Ok(chunks.into_iter().rev().flatten().collect())
+}
+
+// `count` points of the file at `path` holding the powers in `range`, as an
+// arkworks `Vec`: its first ones, or its last ones if `from_end`. A file may
+// hold fewer powers than its range, from the start of it, in which case fewer
+// points are returned.
+fn read_points(
+    path: &Path,
+    range: Range<usize>,
+    count: usize,
+    from_end: bool,
+    trust: TrustPolicy,
+) -> Result<Vec<G1Affine>> {
+    if trust == TrustPolicy::Pinned {
+        check_file(path)?;
+    }
+    let point_size = G1Affine::generator().uncompressed_size();
+    let mut f = File::open(path).map_err(|e| Error::file(path, e))?;
+    let file_size = f.metadata().map_err(|e| Error::file(path, e))?.len();
+    let len = u64::deserialize_uncompressed(&mut f)
+        .map_err(|e| Error::from(e).context(path.display()))? as usize;
+    if len > range.len() || (from_end && len != range.len()) {
+        return Err(Error::MalformedTranscript(format!(
+            "{} holds {len} powers, expected {}",
+            path.display(),
+            range.len()
+        )));
+    }
+    let count = count.min(len);
+    let size = (count * point_size) as u64;
+    if file_size < LEN_SIZE + size {
+        return Err(Error::MalformedTranscript(format!(
+            "{} is too short for {count} points",
+            path.display()
+        )));
+    }
+    let first = if from_end {
+        f.seek(SeekFrom::End(-(size as i64)))?;
+        range.start + len - count
+    } else {
+        range.start
+    };
+    let mut bytes = vec![0u8; size as usize];
+    f.read_exact(&mut bytes).map_err(|e| Error::file(path, e))?;
+    cfg_chunks!(bytes, point_size)
+        .enumerate()
+        .map(|(i, b)| {
+            G1Affine::deserialize_with_mode(b, Compress::No, Validate::Yes)
+                .map_err(|e| Error::InvalidPoint(format!("G1 power {}: {e}", first + i)))
+        })
+        .collect()
+}
+
+// An uncompressed, checked arkworks value, e.g. a point or a map of points
+fn read_file<T: CanonicalDeserialize>(path: &Path, trust: TrustPolicy) -> Result<T> {
+    if trust == TrustPolicy::Pinned {
+        check_file(path)?;
+    }
+    let bytes = fs::read(path).map_err(|e| Error::file(path, e))?;
+    T::deserialize_uncompressed(&bytes[..]).map_err(|e| Error::from(e).context(path.display()))
+}
+
+/// Write `pp` under `dir` in the layout of the published files, read by
+/// [`setup_from_files()`]: its powers of beta split as
+/// [`powers_range()`], the last file holding fewer powers if they end
+/// within it. No shifted powers are written.
+///
+/// Fails unless `pp.h` is [`H`], as the published files assume it.
+pub fn store_files(pp: &UniversalParams<Bls12_377>, dir: impl AsRef<Path>) -> Result<()> {
+    if pp.h != H {
+        return Err(Error::InvalidSrs(
+            "Aleo's files expect snarkVM's generator as h".into(),
+        ));
+    }
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir).map_err(|e| Error::file(dir, e))?;
+    let mut files = Vec::new();
+    for power in MIN_POWER..=MAX_POWER {
+        let range = powers_range(power);
+        if range.start >= pp.powers_of_g.len() {
+            break;
+        }
+        let end = range.end.min(pp.powers_of_g.len());
+        files.push((
+            powers_basename(power),
+            to_bytes(&pp.powers_of_g[range.start..end].to_vec())?,
+        ));
+    }
+    let neg_powers_of_beta = pp
+        .neg_powers_of_h
+        .iter()
+        .map(|(i, p)| {
+            MAX_DEGREE
+                .checked_sub(*i)
+                .map(|bound| (bound, *p))
+                .ok_or_else(|| Error::InvalidSrs(format!("negative power {i} beyond {MAX_DEGREE}")))
+        })
+        .collect::<Result<BTreeMap<usize, G2Affine>>>()?;
+    files.push((BETA_H_FILE.to_string(), to_bytes(&pp.beta_h)?));
+    files.push((
+        POWERS_OF_BETA_GAMMA_FILE.to_string(),
+        to_bytes(&pp.powers_of_gamma_g)?,
+    ));
+    files.push((
+        NEG_POWERS_OF_BETA_FILE.to_string(),
+        to_bytes(&neg_powers_of_beta)?,
+    ));
+    for (name, bytes) in files {
+        let path = dir.join(name);
+        fs::write(&path, bytes).map_err(|e| Error::file(&path, e))?;
+    }
+    Ok(())
+}
+
+// Uncompressed encoding of `value`
+fn to_bytes<T: CanonicalSerialize>(value: &T) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    value.serialize_uncompressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Run setup by parsing the published files of Aleo's universal setup under
+/// `dir`, each checked against its pinned sha256sum, see
+/// [`read_powers_of_g()`].
+///
+/// Unlike [`aztec20::setup_from_raw()`](crate::kzg10::aztec20::setup_from_raw),
+/// `powers_of_gamma_g` and `neg_powers_of_h` are filled in. The latter is keyed
+/// as by arkworks, `i` to `beta^{-i}.h`, so the degree bound `d` of
+/// [`NEG_POWERS_OF_BETA_FILE`] is at `MAX_DEGREE - d`: all of them are kept,
+/// to be paired with the powers of [`read_shifted_powers_of_g()`].
+pub fn setup_from_files(
+    supported_degree: usize,
+    dir: impl AsRef<Path>,
+) -> Result<UniversalParams<Bls12_377>> {
+    read_files(supported_degree, dir.as_ref(), TrustPolicy::Pinned)
+}
+
+// `setup_from_files()`, only checking the files against their pinned
+// sha256sums under `TrustPolicy::Pinned`
+fn read_files(
+    supported_degree: usize,
+    dir: &Path,
+    trust: TrustPolicy,
+) -> Result<UniversalParams<Bls12_377>> {
+    if !(1..=MAX_DEGREE).contains(&supported_degree) {
+        return Err(Error::DegreeOutOfRange {
+            degree: supported_degree,
+            max: MAX_DEGREE,
+        });
+    }
+    let powers_of_g = read_powers_of_g(dir, supported_degree + 1, trust)?;
+    let beta_h: G2Affine = read_file(&dir.join(BETA_H_FILE), trust)?;
+    let powers_of_gamma_g: BTreeMap<usize, G1Affine> =
+        read_file(&dir.join(POWERS_OF_BETA_GAMMA_FILE), trust)?;
+    let path = dir.join(NEG_POWERS_OF_BETA_FILE);
+    let neg_powers_of_h = read_file::<BTreeMap<usize, G2Affine>>(&path, trust)?
+        .into_iter()
+        .map(|(bound, p)| {
+            MAX_DEGREE
+                .checked_sub(bound)
+                .map(|i| (i, p))
+                .ok_or_else(|| {
+                    Error::MalformedTranscript(format!(
+                        "{}: degree bound {bound} beyond {MAX_DEGREE}",
+                        path.display()
+                    ))
+                })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(UniversalParams {
+        powers_of_g,
+        powers_of_gamma_g,
+        h: H,
+        beta_h,
+        neg_powers_of_h,
+        prepared_h: H.into(),
+        prepared_beta_h: beta_h.into(),
+    })
+}
+
+/// Aleo's universal setup, as a [`Ceremony`]: its artifacts are pinned by
+/// [`ALEO_CHECKSUMS`], and it's parsed from the published files in
+/// [`SrsConfig::transcript_dir()`], see [`setup_from_files()`]. Files not
+/// matching their pinned sha256sum (e.g. copies of a smaller setup in the same
+/// layout, see [`store_files()`]) are rejected unless opted in with
+/// [`TrustPolicy::Custom`], in which case all the points parsed are checked
+/// instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aleo;
+
+impl Ceremony for Aleo {
+    type Pairing = Bls12_377;
+
+    const NAME: &'static str = ALEO;
+    const CURVE: CurveId = CurveId::Bls12_377;
+    const MAX_DEGREE: usize = MAX_DEGREE;
+
+    fn artifacts() -> Vec<Artifact> {
+        ALEO_CHECKSUMS
+            .iter()
+            .map(|(degree, sha256)| Artifact {
+                ceremony: Self::NAME.to_string(),
+                degree: *degree,
+                name: Self::artifact_name(*degree, PointEncoding::Uncompressed),
+                sha256: *sha256,
+                encoding: PointEncoding::Uncompressed,
+                chunk_root: None,
+            })
+            .collect()
+    }
+
+    fn setup_from_raw(
+        supported_degree: usize,
+        cfg: &SrsConfig,
+    ) -> Result<UniversalParams<Bls12_377>> {
+        let dir = cfg.transcript_root::<Self>();
+        match cfg.trust {
+            TrustPolicy::Pinned => read_files(supported_degree, &dir, TrustPolicy::Pinned),
+            TrustPolicy::Custom => {
+                let pp = read_files(supported_degree, &dir, TrustPolicy::Custom)?;
+                verify_powers(&pp)?;
+                Ok(pp)
+            },
+        }
+    }
+}
+
+/// Retreive public parameters when given as input the maximum degree degree for
+/// the polynomial commitment scheme.
+/// This API is similar to [KZG10::setup][setup]
+///
+/// # Note
+/// Parameter files are served from the cache and pinned by checksum as for
+/// [`aztec20::setup()`](crate::kzg10::aztec20::setup). No artifact of this
+/// ceremony is released yet: use a release listing some in its manifest (see
+/// [`SrsConfig::release_version()`]), or fill the cache from the published
+/// files with [`cache_from_raw()`].
+///
+/// [setup]: https://docs.rs/ark-poly-commit/0.4.0/ark_poly_commit/kzg10/struct.KZG10.html#method.setup
+pub fn setup(supported_degree: usize) -> Result<UniversalParams<Bls12_377>> {
+    setup_with_config(supported_degree, &SrsConfig::default())
+}
+
+/// Same as [`setup()`], but the cache location, download source and checks
+/// are taken from `cfg` instead of the defaults.
+pub fn setup_with_config(
+    supported_degree: usize,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<Bls12_377>> {
+    ceremony::setup_with_config::<Aleo>(supported_degree, cfg)
+}
+
+/// Fill the default cache with the parameter files used by [`setup()`] for
+/// each of `degrees`, see [`ceremony::prefetch_with_config()`].
+pub fn prefetch(degrees: &[usize]) -> Result<Vec<PathBuf>> {
+    prefetch_with_config(degrees, &SrsConfig::default())
+}
+
+/// Same as [`prefetch()`], filling the cache configured in `cfg`.
+pub fn prefetch_with_config(degrees: &[usize], cfg: &SrsConfig) -> Result<Vec<PathBuf>> {
+    ceremony::prefetch_with_config::<Aleo>(degrees, cfg)
+}
+
+/// Run setup by parsing directly from the published files under
+/// `./data/aleo`, see [`setup_from_files()`].
+pub fn setup_from_raw(supported_degree: usize) -> Result<UniversalParams<Bls12_377>> {
+    setup_from_raw_with_config(supported_degree, &SrsConfig::default())
+}
+
+/// Same as [`setup_from_raw()`], reading the files from the transcript
+/// directory of `cfg`.
+pub fn setup_from_raw_with_config(
+    supported_degree: usize,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<Bls12_377>> {
+    ceremony::setup_from_raw_with_config::<Aleo>(supported_degree, cfg)
+}
+
+/// Parse parameters supporting `supported_degree` from the transcript
+/// directory of `cfg` and store them in its cache, so that [`setup()`] serves
+/// them from then on, see [`ceremony::cache_from_raw_with_config()`]. The
+/// header of the cached file records the sha256sums of the files parsed.
+///
+/// Returns the path of the cached file.
+pub fn cache_from_raw(supported_degree: usize, cfg: &SrsConfig) -> Result<PathBuf> {
+    let hashes = source_hashes(supported_degree, &cfg.transcript_root::<Aleo>())?;
+    ceremony::cache_from_raw_with_config::<Aleo, _>(supported_degree, cfg, hashes)
+}
+
+// The sha256sums of the files parsed for `supported_degree` under `dir`: those
+// of `ALEO_FILE_CHECKSUMS` for the published ones, otherwise hashed
+fn source_hashes(supported_degree: usize, dir: &Path) -> Result<Vec<[u8; 32]>> {
+    (MIN_POWER..=MAX_POWER)
+        .filter(|power| powers_range(*power).start <= supported_degree)
+        .map(powers_basename)
+        .chain(
+            [
+                BETA_H_FILE,
+                POWERS_OF_BETA_GAMMA_FILE,
+                NEG_POWERS_OF_BETA_FILE,
+            ]
+            .map(String::from),
+        )
+        .map(|name| {
+            let path = dir.join(&name);
+            match check_file(&path) {
+                Ok(()) => Ok(ALEO_FILE_CHECKSUMS
+                    .iter()
+                    .find(|(pinned, _)| *pinned == name)
+                    .map(|(_, sha256)| *sha256)
+                    .expect("checked against its pinned sha256sum")),
+                Err(Error::ChecksumMismatch { .. } | Error::UntrustedFile { .. }) => {
+                    let mut f = File::open(&path).map_err(|e| Error::file(&path, e))?;
+                    file_sha256(&mut f)
+                },
+                Err(e) => Err(e),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{load::SrsHeader, VerificationLevel};
+    use anyhow::Result;
+    use ark_bls12_377::Fr;
+    use ark_ec::{pairing::Pairing, CurveGroup};
+    use ark_ff::{Field, UniformRand};
+    use sha2::{Digest, Sha256};
+
+    // the published `beta-h.usrs`, `powers-of-beta-gamma.usrs` and
+    // `neg-powers-of-beta.usrs`, `powers-of-beta-15.usrs` truncated to its
+    // first 256 powers, and `shifted-powers-of-beta-15.usrs` to its last 256
+    const DATA_DIR: &str = "data/aleo";
+    const NUM_DATA_POINTS: usize = 256;
+
+    #[test]
+    fn test_published_files() -> Result<()> {
+        let dir = Path::new(DATA_DIR);
+        for name in [
+            BETA_H_FILE,
+            POWERS_OF_BETA_GAMMA_FILE,
+            NEG_POWERS_OF_BETA_FILE,
+        ] {
+            check_file(&dir.join(name))?;
+        }
+        assert!(matches!(
+            check_file(&dir.join(powers_basename(MIN_POWER))),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            setup_from_files(100, dir),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        // the published files are recorded by their pinned sha256sums
+        assert_eq!(
+            source_hashes(100, dir)?[1..],
+            ALEO_FILE_CHECKSUMS[..3]
+                .iter()
+                .map(|(_, h)| *h)
+                .collect::<Vec<_>>()
+        );
+        let other = tempfile::NamedTempFile::new()?;
+        assert!(matches!(
+            check_file(other.path()),
+            Err(Error::UntrustedFile { .. })
+        ));
+
+        // powers of the same beta, from snarkVM's generators
+        let pp = read_files(NUM_DATA_POINTS - 1, dir, TrustPolicy::Custom)?;
+        verify_powers(&pp)?;
+        assert_eq!(pp.powers_of_gamma_g.len(), 84);
+        assert_eq!(pp.neg_powers_of_h.len(), 27);
+        assert!(read_powers_of_g(dir, NUM_DATA_POINTS + 1, TrustPolicy::Custom).is_err());
+
+        // the last powers, paired with the negative powers of the same degree
+        let shifted = read_shifted_powers_of_g(dir, NUM_DATA_POINTS, TrustPolicy::Custom)?;
+        assert!(read_shifted_powers_of_g(dir, NUM_DATA_POINTS + 1, TrustPolicy::Custom).is_err());
+        let first = NUM_POWERS - NUM_DATA_POINTS;
+        for i in [first, NUM_POWERS - 2] {
+            assert_eq!(
+                Bls12_377::pairing(shifted[i + 1 - first], H),
+                Bls12_377::pairing(shifted[i - first], pp.beta_h)
+            );
+        }
+        let g = pp.powers_of_g[0];
+        let mut paired = 0;
+        for (i, neg_h) in pp.neg_powers_of_h.range(first..) {
+            assert_eq!(
+                Bls12_377::pairing(shifted[i - first], neg_h),
+                Bls12_377::pairing(g, H)
+            );
+            paired += 1;
+        }
+        assert_eq!(paired, 8);
+        Ok(())
+    }
+
+    // the universal setup of degree `max_degree` from a known toxic waste, with
+    // the generators of snarkVM
+    fn mock_setup(max_degree: usize) -> UniversalParams<Bls12_377> {
+        let rng = &mut ark_std::test_rng();
+        let [beta, gamma] = [(); 2].map(|_| Fr::rand(rng));
+        let beta_inv = beta.inverse().unwrap();
+        let (g, h) = (G1Affine::generator(), H);
+        let beta_h = (h * beta).into_affine();
+        let powers = |x: Fr| (0..=max_degree).map(move |i| (i, x.pow([i as u64])));
+        UniversalParams {
+            powers_of_g: powers(beta).map(|(_, b)| (g * b).into_affine()).collect(),
+            powers_of_gamma_g: powers(beta)
+                .map(|(i, b)| (i, (g * (gamma * b)).into_affine()))
+                .collect(),
+            h,
+            beta_h,
+            neg_powers_of_h: powers(beta_inv)
+                .map(|(i, b)| (i, (h * b).into_affine()))
+                .collect(),
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+        }
+    }
+
+    #[test]
+    fn test_aleo_files() -> Result<()> {
+        // the first and last powers of beta across all files
+        let ranges = |range: fn(u32) -> Range<usize>| {
+            let mut ranges: Vec<_> = (MIN_POWER..=MAX_POWER).map(range).collect();
+            ranges.sort_by_key(|r| r.start);
+            ranges
+        };
+        for ranges in [ranges(powers_range), ranges(shifted_powers_range)] {
+            assert!(ranges.windows(2).all(|w| w[0].end == w[1].start));
+            assert_eq!((ranges[0].start, ranges[13].end), (0, NUM_POWERS));
+        }
+
+        let pp = mock_setup(40);
+        let dir = tempfile::tempdir()?;
+        store_files(&pp, dir.path())?;
+        assert_eq!(
+            fs::metadata(dir.path().join(powers_basename(MIN_POWER)))?.len(),
+            LEN_SIZE + 41 * 96
+        );
+        assert_eq!(
+            read_powers_of_g(dir.path(), 41, TrustPolicy::Custom)?,
+            pp.powers_of_g
+        );
+        let mut other = pp.clone();
+        other.h = G2Affine::generator();
+        assert!(store_files(&other, dir.path()).is_err());
+
+        // not the published files
+        let cfg = SrsConfig::default()
+            .transcript_dir(dir.path())
+            .verification(VerificationLevel::Full);
+        assert!(matches!(
+            setup_from_raw_with_config(30, &cfg),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        let cfg = cfg.trust(TrustPolicy::Custom);
+        let parsed = setup_from_raw_with_config(30, &cfg)?;
+        assert_eq!(parsed.powers_of_g, pp.powers_of_g[..31]);
+        assert_eq!(parsed.powers_of_gamma_g, pp.powers_of_gamma_g);
+        assert_eq!(parsed.neg_powers_of_h, pp.neg_powers_of_h);
+        assert_eq!((parsed.h, parsed.beta_h), (pp.h, pp.beta_h));
+        assert!(matches!(
+            setup_from_raw_with_config(41, &cfg),
+            Err(Error::DegreeOutOfRange { max: 40, .. })
+        ));
+
+        // inconsistent negative powers are caught by checking all points
+        let mut bad = pp.clone();
+        let first = bad.neg_powers_of_h[&1];
+        bad.neg_powers_of_h.insert(1, bad.neg_powers_of_h[&2]);
+        bad.neg_powers_of_h.insert(2, first);
+        store_files(&bad, dir.path())?;
+        assert!(setup_from_raw_with_config(30, &cfg).is_err());
+        store_files(&pp, dir.path())?;
+
+        // then served from the cache as any other ceremony
+        let cache = tempfile::tempdir()?;
+        let cfg = cfg.cache_dir(cache.path()).offline(true);
+        let path = cache_from_raw(30, &cfg)?;
+        assert_eq!(path.parent(), Some(cache.path().join(ALEO).as_path()));
+        // recording the files parsed
+        let parsed_files = [
+            powers_basename(MIN_POWER).as_str(),
+            BETA_H_FILE,
+            POWERS_OF_BETA_GAMMA_FILE,
+            NEG_POWERS_OF_BETA_FILE,
+        ]
+        .map(|name| {
+            Ok(hex::encode(Sha256::digest(fs::read(
+                dir.path().join(name),
+            )?)))
+        });
+        assert_eq!(
+            SrsHeader::read(&path)?.unwrap().source_hashes,
+            parsed_files.into_iter().collect::<Result<Vec<_>>>()?
+        );
+        let loaded = setup_with_config(20, &cfg)?;
+        assert_eq!(loaded.powers_of_g, pp.powers_of_g[..21]);
+        assert_eq!(loaded.neg_powers_of_h, parsed.neg_powers_of_h);
+        assert_eq!(loaded.powers_of_gamma_g, pp.powers_of_gamma_g);
+        Ok(())
+    }
+}
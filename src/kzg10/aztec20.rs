@@ -2,37 +2,199 @@
 //! generated.
 //! For concrete details: <https://github.com/AztecProtocol/ignition-verification>
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Result};
 use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G2Affine};
 use ark_ec::AffineRepr;
 use ark_ff::{BigInteger256, PrimeField};
 use ark_poly_commit::kzg10::UniversalParams;
 use ark_std::{
+    cfg_chunks,
     collections::BTreeMap,
     format,
     fs::File,
     io::{Read, Seek, SeekFrom},
-    iterable::Iterable,
+    string::ToString,
     vec,
     vec::Vec,
 };
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 use crate::{
-    constants::{self, AZTEC20_DIR},
-    load::{
-        download_srs_file,
-        kzg10::bn254::aztec::{default_path, load_aztec_srs},
+    ceremony::{self, Ceremony},
+    config::SrsConfig,
+    constants::{
+        AZTEC20_CHECKSUMS, AZTEC20_CHUNK_ROOTS, AZTEC20_COMPRESSED_CHECKSUMS,
+        AZTEC20_COMPRESSED_CHUNK_ROOTS,
     },
+    errors::{Error, Result},
+    load::{CurveId, PointEncoding},
+    manifest::{Artifact, AZTEC20},
 };
 
-const NUM_TRANSCRIPTS: usize = 20;
-const NUM_G1_PER_TRANSCRIPT: usize = 5_040_000;
-const NUM_G2: usize = 2;
-const G1_STARTING_POS: u64 = 28; // pos of the first G1 points in transcript file
-const NUM_BIGINT_PER_G1: usize = 2;
-const NUM_BIGINT_PER_G2: usize = 4;
+// 20 transcripts of 5,040,000 G1 points each
+const MAX_DEGREE: usize = 100_800_000;
+pub(crate) const G1_SIZE: usize = 64;
+pub(crate) const G2_SIZE: usize = 128;
+// number of G1 points read from a transcript at once, i.e. 4 MB
+pub(crate) const CHUNK_SIZE: usize = 1 << 16;
+
+/// Manifest at the start of every `transcriptNN.dat`, all fields are stored
+/// as big-endian `u32`.
+/// Concrete format spec:
+/// <https://github.com/AztecProtocol/ignition-verification/blob/master/Transcript_spec.md#structure-of-a-transcript-file>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TranscriptManifest {
+    /// index of this transcript file
+    pub transcript_number: u32,
+    /// number of transcript files of the ceremony
+    pub total_transcripts: u32,
+    /// number of G1 points over all transcript files
+    pub total_g1_points: u32,
+    /// number of G2 points over all transcript files
+    pub total_g2_points: u32,
+    /// number of G1 points in this transcript file
+    pub num_g1_points: u32,
+    /// number of G2 points in this transcript file
+    pub num_g2_points: u32,
+    /// index of the first G1 point of this file among all G1 points
+    pub start_from: u32,
+}
+
+impl TranscriptManifest {
+    /// Size of the serialized manifest in bytes
+    pub const SIZE: usize = 28;
+    /// Size of the BLAKE2b checksum at the end of each transcript file
+    pub const CHECKSUM_SIZE: usize = 64;
+
+    /// Parse the manifest from the start of a transcript file
+    pub fn read<R: Read>(r: &mut R) -> Result<Self> {
+        let mut buf = [0u8; Self::SIZE];
+        r.read_exact(&mut buf)?;
+        Ok(Self::from_bytes(&buf))
+    }
+
+    /// Parse the manifest from its serialized bytes
+    pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Self {
+        let field = |i: usize| {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(&bytes[i * 4..(i + 1) * 4]);
+            u32::from_be_bytes(buf)
+        };
+        Self {
+            transcript_number: field(0),
+            total_transcripts: field(1),
+            total_g1_points: field(2),
+            total_g2_points: field(3),
+            num_g1_points: field(4),
+            num_g2_points: field(5),
+            start_from: field(6),
+        }
+    }
+
+    /// Serialize the manifest as in the transcript files
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        let fields = [
+            self.transcript_number,
+            self.total_transcripts,
+            self.total_g1_points,
+            self.total_g2_points,
+            self.num_g1_points,
+            self.num_g2_points,
+            self.start_from,
+        ];
+        for (chunk, field) in bytes.chunks_exact_mut(4).zip(fields) {
+            chunk.copy_from_slice(&field.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Expected size of the whole transcript file, including the checksum
+    pub fn file_size(&self) -> u64 {
+        (Self::SIZE + Self::CHECKSUM_SIZE) as u64
+            + self.num_g1_points as u64 * G1_SIZE as u64
+            + self.num_g2_points as u64 * G2_SIZE as u64
+    }
+}
+
+/// Aztec's ignition ceremony, as a [`Ceremony`]: its artifacts are pinned by
+/// [`AZTEC20_CHECKSUMS`], and it's parsed from the transcripts in
+/// [`SrsConfig::transcript_dir()`]. Files with compressed points are only
+/// pinned by [`AZTEC20_COMPRESSED_CHECKSUMS`] when loaded from a path, or
+/// served from a release whose manifest lists them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aztec20;
+
+impl Aztec20 {
+    /// The files with compressed points reproduced by
+    /// [`compress_aztec_srs()`](crate::load::kzg10::bn254::aztec::compress_aztec_srs)
+    /// from the artifacts, as pinned by [`AZTEC20_COMPRESSED_CHECKSUMS`]. They
+    /// aren't published in the default release, so only for the manifest of a
+    /// release hosting them.
+    pub fn compressed_artifacts() -> Vec<Artifact> {
+        Self::artifacts_of(
+            &AZTEC20_COMPRESSED_CHECKSUMS,
+            &AZTEC20_COMPRESSED_CHUNK_ROOTS,
+            PointEncoding::Compressed,
+        )
+    }
+
+    fn artifacts_of(
+        checksums: &[(usize, [u8; 32])],
+        chunk_roots: &[([u8; 32], [u8; 32])],
+        encoding: PointEncoding,
+    ) -> Vec<Artifact> {
+        checksums
+            .iter()
+            .map(|(degree, sha256)| Artifact {
+                ceremony: Self::NAME.to_string(),
+                degree: *degree,
+                name: Self::artifact_name(*degree, encoding),
+                sha256: *sha256,
+                encoding,
+                chunk_root: chunk_roots
+                    .iter()
+                    .find(|(cksum, _)| cksum == sha256)
+                    .map(|(_, root)| *root),
+            })
+            .collect()
+    }
+}
+
+impl Ceremony for Aztec20 {
+    type Pairing = Bn254;
+
+    const NAME: &'static str = AZTEC20;
+    const CURVE: CurveId = CurveId::Bn254;
+    const MAX_DEGREE: usize = MAX_DEGREE;
+
+    fn artifacts() -> Vec<Artifact> {
+        Self::artifacts_of(
+            &AZTEC20_CHECKSUMS,
+            &AZTEC20_CHUNK_ROOTS,
+            PointEncoding::Uncompressed,
+        )
+    }
+
+    fn checksums() -> Vec<(usize, [u8; 32])> {
+        [&AZTEC20_CHECKSUMS[..], &AZTEC20_COMPRESSED_CHECKSUMS[..]].concat()
+    }
+
+    fn chunk_roots() -> Vec<([u8; 32], [u8; 32])> {
+        [
+            &AZTEC20_CHUNK_ROOTS[..],
+            &AZTEC20_COMPRESSED_CHUNK_ROOTS[..],
+        ]
+        .concat()
+    }
+
+    fn setup_from_raw(supported_degree: usize, cfg: &SrsConfig) -> Result<UniversalParams<Bn254>> {
+        setup_from_transcripts(supported_degree, cfg.transcript_root::<Self>())
+    }
+}
 
 /// Retreive public parameters when given as input the maximum degree degree for
 /// the polynomial commitment scheme.
@@ -49,73 +211,93 @@ const NUM_BIGINT_PER_G2: usize = 4;
 /// we assume you have a local cache of the parameter binary file at
 /// `AZTEC_SRS_PATH` (see `.env` file),
 /// which you can load using `dotenv::dotenv().ok();` or you can use
-/// `std::env::set_var("AZTEC_SRS_PATH", YOUR_CUSTOM_PATH)`
+/// `std::env::set_var("AZTEC_SRS_PATH", YOUR_CUSTOM_PATH)`.
+/// Use [`setup_with_config()`] to choose the location without touching the
+/// environment.
+///
+/// Missing files are downloaded, unless `ARK_SRS_OFFLINE` is set, in which
+/// case [`Error::NotCached`] is returned instead. Use [`prefetch()`] to fill
+/// the cache ahead of time.
 ///
 /// [setup]: https://docs.rs/ark-poly-commit/0.4.0/ark_poly_commit/kzg10/struct.KZG10.html#method.setup
 pub fn setup(supported_degree: usize) -> Result<UniversalParams<Bn254>> {
-    if !(1..=NUM_G1_PER_TRANSCRIPT * NUM_TRANSCRIPTS).contains(&supported_degree) {
-        bail!("Max degree has to be between [1, 100.8 million].");
-    }
+    setup_with_config(supported_degree, &SrsConfig::default())
+}
+
+/// Same as [`setup()`], but the cache location, download source and checks
+/// are taken from `cfg` instead of the defaults.
+///
+/// `AZTEC_SRS_PATH`, if set, still overrides the parameter file path.
+pub fn setup_with_config(
+    supported_degree: usize,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<Bn254>> {
     let param_file = match std::env::var("AZTEC_SRS_PATH") {
         Ok(path) => {
             tracing::info!("Using AZTEC_SRS_PATH {path}");
-            PathBuf::from(path)
-        },
-        Err(_) => {
-            // By default, we pre-serialized a few common degrees but may not be *exactly*
-            // `supported_degree` requested, thus attempts to download the corresponding
-            // parameter files will fail. Thus, we try to find the next higher one than
-            // requested instead.
-            let supported: Vec<usize> = constants::AZTEC20_CHECKSUMS
-                .iter()
-                .map(|(d, _)| d)
-                .collect();
-            let next_higher_degree =
-                match supported.iter().filter(|d| **d >= supported_degree).min() {
-                    Some(d) => *d,
-                    None => bail!("Pre-serialized supported degrees: {:?}", supported),
-                };
-            tracing::info!("Requested degree {supported_degree} using next higher available degree {next_higher_degree}");
-            default_path(None, next_higher_degree)?
+            Some(PathBuf::from(path))
         },
+        Err(_) => None,
     };
-    setup_helper(supported_degree, param_file)
+    setup_helper(supported_degree, param_file, cfg)
 }
 
 // Setup helper to allow passing param_file for tests because setting
 // environment variables is prone to errors because they are shared by all the
 // tests.
-fn setup_helper(supported_degree: usize, param_file: PathBuf) -> Result<UniversalParams<Bn254>> {
-    // Download SRS file if it doesn't exist
-    if !param_file.exists() {
-        tracing::info!("SRS file {param_file:?} does not exist");
-        download_srs_file(
-            param_file
-                .file_name()
-                .expect("Failed to get basename")
-                .to_str()
-                .expect("Basename is invalid str"),
-            &param_file,
-        )
-        .expect("Failed to download SRS file.")
-    } else {
-        tracing::info!("SRS file already exists.");
-    }
-    match load_aztec_srs(supported_degree, param_file) {
-        Ok(pp) => Ok(pp),
-        Err(e) => bail!("Pre-serialized params not found, either download from released assets or use `setup_from_raw()`: {}", e)
-    }
+fn setup_helper(
+    supported_degree: usize,
+    param_file: Option<PathBuf>,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<Bn254>> {
+    ceremony::setup_helper::<Aztec20>(supported_degree, param_file, cfg)
+}
+
+/// Fill the default cache with the parameter files used by [`setup()`] for
+/// each of `degrees`, e.g. in a build step ahead of running offline, see
+/// [`SrsConfig::offline()`].
+///
+/// Files already cached are checked against their pinned checksum instead of
+/// being downloaded again. Returns the path of the file for each degree.
+pub fn prefetch(degrees: &[usize]) -> Result<Vec<PathBuf>> {
+    prefetch_with_config(degrees, &SrsConfig::default())
+}
+
+/// Same as [`prefetch()`], filling the cache configured in `cfg`.
+pub fn prefetch_with_config(degrees: &[usize], cfg: &SrsConfig) -> Result<Vec<PathBuf>> {
+    ceremony::prefetch_with_config::<Aztec20>(degrees, cfg)
 }
 
 /// Run setup by parsing directly from Aztec's original transcript file.
 /// See details from [`setup()`].
 pub fn setup_from_raw(supported_degree: usize) -> Result<UniversalParams<Bn254>> {
-    if !(1..=NUM_G1_PER_TRANSCRIPT * NUM_TRANSCRIPTS).contains(&supported_degree) {
-        bail!("Max degree has to be between [1, 100.8 million].");
-    }
+    setup_from_raw_with_config(supported_degree, &SrsConfig::default())
+}
 
+/// Same as [`setup_from_raw()`], reading the transcripts from the transcript
+/// directory of `cfg`.
+pub fn setup_from_raw_with_config(
+    supported_degree: usize,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<Bn254>> {
+    ceremony::setup_from_raw_with_config::<Aztec20>(supported_degree, cfg)
+}
+
+/// Run setup by parsing transcript files `transcriptNN.dat` under `dir`, in
+/// the format of Aztec's ignition ceremony but of any size: the layout of
+/// each file is read from its [`TranscriptManifest`].
+pub fn setup_from_transcripts(
+    supported_degree: usize,
+    dir: impl AsRef<Path>,
+) -> Result<UniversalParams<Bn254>> {
+    if supported_degree == 0 {
+        return Err(Error::DegreeOutOfRange {
+            degree: supported_degree,
+            max: MAX_DEGREE,
+        });
+    }
     let mut powers_of_g = vec![G1Affine::generator()];
-    powers_of_g.extend_from_slice(&parse_g1_points(supported_degree)?);
+    powers_of_g.extend_from_slice(&parse_g1_points(dir.as_ref(), supported_degree)?);
 
     // NOTE: used for hiding variant of KZG, not supported in Aztec's CRS.
     let powers_of_gamma_g = BTreeMap::new();
@@ -123,7 +305,7 @@ pub fn setup_from_raw(supported_degree: usize) -> Result<UniversalParams<Bn254>>
     let neg_powers_of_h = BTreeMap::new();
 
     let h = G2Affine::generator();
-    let [beta_h, _] = parse_g2_points()?;
+    let beta_h = parse_g2_points(dir.as_ref())?[0];
     let prepared_h = h.into();
     let prepared_beta_h = beta_h.into();
 
@@ -139,65 +321,185 @@ pub fn setup_from_raw(supported_degree: usize) -> Result<UniversalParams<Bn254>>
     Ok(pp)
 }
 
+// Open the `index`-th transcript file under `dir`, and check its size matches
+// the layout in its manifest.
+fn open_transcript(dir: &Path, index: usize) -> Result<(File, TranscriptManifest)> {
+    let path = dir.join(format!("transcript{index:02}.dat"));
+    let mut f = File::open(&path).map_err(|e| Error::file(&path, e))?;
+    let manifest = TranscriptManifest::read(&mut f).map_err(|_| {
+        Error::MalformedTranscript(format!("{} is too short for a manifest", path.display()))
+    })?;
+    if manifest.transcript_number as usize != index {
+        return Err(Error::MalformedTranscript(format!(
+            "{} has transcript number {}",
+            path.display(),
+            manifest.transcript_number
+        )));
+    }
+    let file_size = f.metadata().map_err(|e| Error::file(&path, e))?.len();
+    if file_size != manifest.file_size() {
+        return Err(Error::MalformedTranscript(format!(
+            "{} has {file_size} bytes, but its manifest expects {} bytes: truncated or mismatched file",
+            path.display(),
+            manifest.file_size()
+        )));
+    }
+    Ok((f, manifest))
+}
+
 // Returns x.[1], x^2.[1], ... , x^`bound`.[1] where `x` is toxic
 // waste/trapdoor, [1] is G1 generator (1, 2).
-fn parse_g1_points(bound: usize) -> Result<Vec<G1Affine>> {
-    if bound > NUM_TRANSCRIPTS * NUM_G1_PER_TRANSCRIPT {
-        bail!("Aztec ceremoy only supports up to 100.8 million degree.");
+fn parse_g1_points(dir: &Path, bound: usize) -> Result<Vec<G1Affine>> {
+    let mut g1_points = Vec::with_capacity(bound);
+    let mut index = 0;
+    while g1_points.len() < bound {
+        let (mut f, manifest) = open_transcript(dir, index)?;
+        if index == 0 && bound > manifest.total_g1_points as usize {
+            return Err(Error::DegreeOutOfRange {
+                degree: bound,
+                max: manifest.total_g1_points as usize,
+            });
+        }
+        if manifest.start_from as usize != g1_points.len() {
+            return Err(Error::MalformedTranscript(format!(
+                "transcript{index:02}.dat starts from G1 point {}, expect {}",
+                manifest.start_from,
+                g1_points.len()
+            )));
+        }
+        if index >= manifest.total_transcripts as usize {
+            return Err(Error::MalformedTranscript(
+                "Missing G1 points after the last transcript".into(),
+            ));
+        }
+        let num_points = (manifest.num_g1_points as usize).min(bound - g1_points.len());
+        g1_points.extend(parse_g1_points_from_file(&mut f, &manifest, num_points)?);
+        index += 1;
     }
-    let mut g1_points = Vec::new();
-    let num_full_transcript = bound / NUM_G1_PER_TRANSCRIPT;
-    let remainder_num_points = bound - num_full_transcript * NUM_G1_PER_TRANSCRIPT;
+    Ok(g1_points)
+}
 
-    for file_idx in 0..num_full_transcript {
-        let mut f = File::open(format!("{}/transcript{:02}.dat", AZTEC20_DIR, file_idx))?;
-        g1_points.extend_from_slice(&parse_g1_points_from_file(&mut f, NUM_G1_PER_TRANSCRIPT)?);
+// Each coordinate is stored as 4 u64 limbs from the least significant one, each
+// limb in big-endian.
+fn bytes_to_fq(bytes: &[u8]) -> Result<Fq> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(chunk);
+        *limb = u64::from_be_bytes(buf);
     }
+    Fq::from_bigint(BigInteger256::new(limbs))
+        .ok_or_else(|| Error::InvalidPoint("Field element is not reduced".into()))
+}
+
+// The inverse of `bytes_to_fq()`.
+fn fq_to_bytes(f: &Fq) -> Vec<u8> {
+    f.into_bigint()
+        .0
+        .iter()
+        .flat_map(|limb| limb.to_be_bytes())
+        .collect()
+}
 
-    let mut f = File::open(format!(
-        "{}/transcript{:02}.dat",
-        AZTEC20_DIR, num_full_transcript
-    ))?;
-    g1_points.extend_from_slice(&parse_g1_points_from_file(&mut f, remainder_num_points)?);
+/// Encode a G1 point into `G1_SIZE` bytes in the transcript encoding, the
+/// inverse of [`bytes_to_g1()`].
+pub(crate) fn g1_to_bytes(p: &G1Affine) -> Vec<u8> {
+    [p.x, p.y].iter().flat_map(fq_to_bytes).collect()
+}
 
-    Ok(g1_points)
+/// Encode a G2 point into `G2_SIZE` bytes in the transcript encoding, the
+/// inverse of [`bytes_to_g2()`].
+pub(crate) fn g2_to_bytes(p: &G2Affine) -> Vec<u8> {
+    [p.x.c0, p.x.c1, p.y.c0, p.y.c1]
+        .iter()
+        .flat_map(fq_to_bytes)
+        .collect()
+}
+
+/// Decode a G1 point of `G1_SIZE` bytes in the transcript encoding, checking
+/// it's on the curve and in the prime order subgroup.
+pub(crate) fn bytes_to_g1(bytes: &[u8]) -> Result<G1Affine> {
+    let point = G1Affine::new_unchecked(
+        bytes_to_fq(&bytes[..32])?,
+        bytes_to_fq(&bytes[32..G1_SIZE])?,
+    );
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidPoint(
+            "G1 point not on curve or not in the prime order subgroup".into(),
+        ));
+    }
+    Ok(point)
+}
+
+/// Decode a G2 point of `G2_SIZE` bytes in the transcript encoding, checking
+/// it's on the curve and in the prime order subgroup.
+pub(crate) fn bytes_to_g2(bytes: &[u8]) -> Result<G2Affine> {
+    let x = Fq2::new(bytes_to_fq(&bytes[..32])?, bytes_to_fq(&bytes[32..64])?);
+    let y = Fq2::new(
+        bytes_to_fq(&bytes[64..96])?,
+        bytes_to_fq(&bytes[96..G2_SIZE])?,
+    );
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidPoint(
+            "G2 point not on curve or not in the prime order subgroup".into(),
+        ));
+    }
+    Ok(point)
+}
+
+/// Decode consecutive G1 points in the transcript encoding, in parallel
+/// with the `parallel` feature. `offset` is the index of the first point, only
+/// used for error reporting.
+pub(crate) fn bytes_to_g1_points(bytes: &[u8], offset: usize) -> Result<Vec<G1Affine>> {
+    if !bytes.len().is_multiple_of(G1_SIZE) {
+        return Err(Error::MalformedTranscript(format!(
+            "Expect a multiple of {G1_SIZE} bytes for G1 points"
+        )));
+    }
+    cfg_chunks!(bytes, G1_SIZE)
+        .enumerate()
+        .map(|(i, b)| {
+            bytes_to_g1(b).map_err(|e| e.context(format_args!("G1 point {}", offset + i)))
+        })
+        .collect()
 }
 
 // Parse G1Affine points from CRS
 // Concrete format spec:
 // https://github.com/AztecProtocol/ignition-verification/blob/master/Transcript_spec.md#structure-of-a-transcript-file
-#[allow(clippy::needless_range_loop)]
-fn parse_g1_points_from_file(f: &mut File, num_points: usize) -> Result<Vec<G1Affine>> {
-    let mut g1_points = Vec::new();
-
-    if num_points > NUM_G1_PER_TRANSCRIPT {
-        bail!("Internal Error, should not retrieve more than 5 million points per file");
-    }
-
-    for i in 0..num_points {
-        // [X, Y]
-        let mut bigints_repr = [[0u64; 4]; NUM_BIGINT_PER_G1];
-        for j in 0..NUM_BIGINT_PER_G1 {
-            for k in 0usize..4 {
-                // size of G1 is 64
-                // size of a bigint is 32 bytes.
-                // size of u64 is 8 bytes.
-                f.seek(SeekFrom::Start(
-                    G1_STARTING_POS + i as u64 * 64 + j as u64 * 32 + k as u64 * 8,
-                ))?;
-                let mut buf = [0u8; 8];
-                f.read_exact(&mut buf)?;
-                bigints_repr[j][k] = u64::from_be_bytes(buf);
-            }
-        }
+fn parse_g1_points_from_file(
+    f: &mut File,
+    manifest: &TranscriptManifest,
+    num_points: usize,
+) -> Result<Vec<G1Affine>> {
+    if num_points > manifest.num_g1_points as usize {
+        return Err(Error::DegreeOutOfRange {
+            degree: num_points,
+            max: manifest.num_g1_points as usize,
+        });
+    }
 
-        let x = Fq::from_bigint(BigInteger256::new(bigints_repr[0]))
-            .expect("Failed to parse G1 point's x-coordinate");
-        let y = Fq::from_bigint(BigInteger256::new(bigints_repr[1]))
-            .expect("Failed to parse G1 point's y-coordinate");
+    // G1 points right after the manifest, each of `G1_SIZE` bytes: [X, Y].
+    f.seek(SeekFrom::Start(TranscriptManifest::SIZE as u64))?;
+    read_g1_points(f, num_points).map_err(|e| {
+        e.context(format_args!(
+            "transcript{:02}.dat",
+            manifest.transcript_number
+        ))
+    })
+}
 
-        let point = G1Affine::new(x, y); // point.is_on_curve() already checked during `new()`
-        g1_points.push(point);
+/// Read `num_points` consecutive G1 points in the transcript encoding from
+/// `r`, in large chunks decoded in memory, see [`bytes_to_g1_points()`].
+pub(crate) fn read_g1_points<R: Read>(r: &mut R, num_points: usize) -> Result<Vec<G1Affine>> {
+    let mut g1_points = Vec::with_capacity(num_points);
+    let mut buf = vec![0u8; num_points.min(CHUNK_SIZE) * G1_SIZE];
+    while g1_points.len() < num_points {
+        let n = (num_points - g1_points.len()).min(CHUNK_SIZE);
+        let bytes = &mut buf[..n * G1_SIZE];
+        r.read_exact(bytes)?;
+        g1_points.extend(bytes_to_g1_points(bytes, g1_points.len())?);
     }
     Ok(g1_points)
 }
@@ -205,68 +507,96 @@ fn parse_g1_points_from_file(f: &mut File, num_points: usize) -> Result<Vec<G1Af
 // Parse G2Affine points from CRS
 // Concrete format spec:
 // https://github.com/AztecProtocol/ignition-verification/blob/master/Transcript_spec.md#structure-of-a-transcript-file
-// NOTE: the second G2 point is not used in CRS, but only for transcript
-// verification purposes.
-#[allow(clippy::needless_range_loop)]
-fn parse_g2_points() -> Result<[G2Affine; NUM_G2]> {
-    let mut g2_points = [G2Affine::default(); NUM_G2];
-
-    // Parse 2 G2Affine points from CRS
-    let mut f = File::open(format!("{}/transcript00.dat", AZTEC20_DIR))?;
+// NOTE: only the first G2 point x.[1]_2 is used in CRS, the others are only
+// for transcript verification purposes.
+fn parse_g2_points(dir: &Path) -> Result<Vec<G2Affine>> {
+    let (mut f, manifest) = open_transcript(dir, 0)?;
+    if manifest.num_g2_points == 0 {
+        return Err(Error::MalformedTranscript(
+            "transcript00.dat contains no G2 point".into(),
+        ));
+    }
 
-    for i in 0..NUM_G2 {
+    let mut g2_points = Vec::with_capacity(manifest.num_g2_points as usize);
+    let mut buf = [0u8; G2_SIZE];
+    for i in 0..manifest.num_g2_points as usize {
+        // G2 points after all G1 points, each of `G2_SIZE` bytes:
         // [x.c0, x.c1, y.c0, y.c1]
-        let mut bigints_repr = [[0u64; 4]; NUM_BIGINT_PER_G2];
-
-        for j in 0..NUM_BIGINT_PER_G2 {
-            for k in 0usize..4 {
-                // size of G1 is 64
-                // size of G2 is 128 bytes (4 * 32).
-                // size of a bigint is 32 bytes.
-                // size of u64 is 8 bytes.
-                f.seek(SeekFrom::Start(
-                    G1_STARTING_POS
-                        + NUM_G1_PER_TRANSCRIPT as u64 * 64
-                        + i as u64 * 128
-                        + j as u64 * 32
-                        + k as u64 * 8,
-                ))?;
-                let mut buf = [0u8; 8];
-                f.read_exact(&mut buf)?;
-                bigints_repr[j][k] = u64::from_be_bytes(buf);
-            }
-        }
-        let x_c0 = Fq::from_bigint(BigInteger256::new(bigints_repr[0]))
-            .expect("Failed to parse G2 point's X.c0");
-        let x_c1 = Fq::from_bigint(BigInteger256::new(bigints_repr[1]))
-            .expect("Failed to parse G2 point's X.c1");
-        let y_c0 = Fq::from_bigint(BigInteger256::new(bigints_repr[2]))
-            .expect("Failed to parse G2 point's Y.c0");
-        let y_c1 = Fq::from_bigint(BigInteger256::new(bigints_repr[3]))
-            .expect("Failed to parse G2 point's Y.c1");
-        let x = Fq2::new(x_c0, x_c1);
-        let y = Fq2::new(y_c0, y_c1);
-
-        let point = G2Affine::new(x, y);
-        assert!(point.is_on_curve());
-        g2_points[i] = point;
+        f.seek(SeekFrom::Start(
+            (TranscriptManifest::SIZE + manifest.num_g1_points as usize * G1_SIZE + i * G2_SIZE)
+                as u64,
+        ))?;
+        f.read_exact(&mut buf)?;
+        g2_points.push(bytes_to_g2(&buf).map_err(|e| e.context(format_args!("G2 point {i}")))?);
     }
-
     Ok(g2_points)
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
     use super::*;
+    use crate::download::{
+        test::{closed_port_url, serve_dir},
+        Downloader,
+    };
+    use crate::{
+        config::{Decompression, VerificationLevel},
+        constants::RELEASE_VERSION,
+        load::{
+            kzg10::bn254::aztec::{compress_aztec_srs, compressed_path, default_path},
+            ChunkIndex, QuarantineRecord,
+        },
+        manifest::ArtifactManifest,
+    };
+    use anyhow::{bail, Result};
+    use ark_bn254::Fr;
     use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
-    use ark_ff::UniformRand;
+    use ark_ff::{Field, UniformRand};
     use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, Polynomial};
     use ark_poly_commit::{
         kzg10::{Powers, Proof, Randomness, VerifierKey, KZG10},
         PCCommitmentState,
     };
     use ark_std::ops::Div;
+    use blake2::{Blake2b512, Digest};
+    use core::time::Duration;
     use dotenv::dotenv;
+    use std::{io::Write, path::Path};
+
+    /// Write mock `transcriptNN.dat` files of the given sizes into `dir`, with
+    /// G1 points `tau^1.[1]` onwards and G2 points `[tau.[1]_2, tau^2.[1]_2]`
+    /// in transcript 0.
+    pub(crate) fn write_mock_transcripts(dir: &Path, tau: Fr, sizes: &[u32]) -> Result<()> {
+        let total: u32 = sizes.iter().sum();
+        let mut start_from = 0;
+        for (i, num_g1_points) in sizes.iter().enumerate() {
+            let num_g2_points = if i == 0 { 2 } else { 0 };
+            let manifest = TranscriptManifest {
+                transcript_number: i as u32,
+                total_transcripts: sizes.len() as u32,
+                total_g1_points: total,
+                total_g2_points: 2,
+                num_g1_points: *num_g1_points,
+                num_g2_points,
+                start_from,
+            };
+            let mut bytes = manifest.to_bytes().to_vec();
+            for j in 0..*num_g1_points {
+                let p =
+                    (G1Affine::generator() * tau.pow([(start_from + j + 1) as u64])).into_affine();
+                bytes.extend(g1_to_bytes(&p));
+            }
+            for j in 0..num_g2_points {
+                let p = (G2Affine::generator() * tau.pow([j as u64 + 1])).into_affine();
+                bytes.extend(g2_to_bytes(&p));
+            }
+            let checksum = Blake2b512::digest(&bytes);
+            bytes.extend_from_slice(&checksum);
+            File::create(dir.join(format!("transcript{i:02}.dat")))?.write_all(&bytes)?;
+            start_from += num_g1_points;
+        }
+        Ok(())
+    }
 
     // simplify from arkworks' poly-commit
     pub fn open<'c, E, P>(
@@ -376,6 +706,205 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_setup_from_transcripts() -> Result<()> {
+        let tau = Fr::rand(&mut ark_std::test_rng());
+        let dir = tempfile::tempdir()?;
+        write_mock_transcripts(dir.path(), tau, &[100, 100, 50])?;
+
+        let pp = setup_from_transcripts(220, dir.path())?;
+        assert_eq!(pp.powers_of_g.len(), 221);
+        assert_eq!(
+            pp.powers_of_g[150],
+            (G1Affine::generator() * tau.pow([150])).into_affine()
+        );
+        assert_eq!(pp.beta_h, (G2Affine::generator() * tau).into_affine());
+        crate::verify::kzg10::verify_powers(&pp)?;
+        assert_eq!(
+            setup_from_transcripts(250, dir.path())?.powers_of_g.len(),
+            251
+        );
+        assert!(setup_from_transcripts(251, dir.path()).is_err());
+
+        // truncated file
+        let path = dir.path().join("transcript01.dat");
+        let bytes = std::fs::read(&path)?;
+        std::fs::write(&path, &bytes[..bytes.len() - 1])?;
+        assert!(setup_from_transcripts(100, dir.path()).is_ok());
+        let err = setup_from_transcripts(101, dir.path()).unwrap_err();
+        assert!(format!("{err}").contains("truncated"));
+
+        // mismatched manifest
+        let mut bad = bytes;
+        bad[27] += 1; // start_from
+        std::fs::write(&path, bad)?;
+        assert!(setup_from_transcripts(101, dir.path()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_setup_with_config() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cfg = SrsConfig::default()
+            .cache_dir(dir.path())
+            .offline(true)
+            .verification(VerificationLevel::Full);
+        assert!(matches!(
+            setup_helper(1000, None, &cfg),
+            Err(Error::NotCached { .. })
+        ));
+
+        let path = default_path(cfg.cache_dir.clone(), 1024)?;
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::copy("data/aztec20/kzg10-aztec20-srs-1024.bin", &path)?;
+        let pp = setup_helper(1000, None, &cfg)?;
+        assert_eq!(pp.powers_of_g.len(), 1001);
+        assert_eq!(setup_helper(1000, Some(path.clone()), &cfg)?, pp);
+
+        // any smaller degree is served by the cached file, trimmed if asked to
+        let cache = path.parent().unwrap().to_path_buf();
+        let pp = setup_helper(300, Some(cache.clone()), &cfg.clone().trim_cached(true))?;
+        assert_eq!(pp.powers_of_g.len(), 301);
+        assert!(cache.join("kzg10-aztec20-srs-300-local.bin").exists());
+        assert_eq!(setup_helper(300, None, &cfg)?, pp);
+
+        let cfg = cfg
+            .mirrors(["http://localhost:8080/releases/"])
+            .add_mirror("file:///srv/ark-srs")
+            .release_version("1.0.0");
+        assert_eq!(
+            cfg.release_urls("srs.bin"),
+            [
+                "http://localhost:8080/releases/v1.0.0/srs.bin",
+                "file:///srv/ark-srs/v1.0.0/srs.bin"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_from_mirrors() -> Result<()> {
+        // a new release, only known from its manifest
+        let mirror = tempfile::tempdir()?;
+        let release = mirror.path().join("v9.9.9");
+        std::fs::create_dir_all(&release)?;
+        let mut manifest = ArtifactManifest::builtin();
+        manifest.version = "9.9.9".into();
+        manifest.artifacts.retain(|a| a.degree == 1024);
+        manifest.artifacts.extend(Aztec20::compressed_artifacts());
+        std::fs::write(release.join(ArtifactManifest::FILENAME), manifest.to_json())?;
+        let artifact = release.join("kzg10-aztec20-srs-1024.bin");
+        std::fs::copy("data/aztec20/kzg10-aztec20-srs-1024.bin", &artifact)?;
+
+        // unreachable mirrors are skipped
+        let cache = tempfile::tempdir()?;
+        let cfg = SrsConfig::default()
+            .downloader(Downloader::default().backoff(Duration::from_millis(1)))
+            .cache_dir(cache.path())
+            .release_version("9.9.9")
+            .mirrors([
+                closed_port_url(),
+                serve_dir(mirror.path().to_path_buf(), 0, 0).url,
+            ]);
+        let pp = setup_helper(1000, None, &cfg)?;
+        assert_eq!(pp.powers_of_g.len(), 1001);
+        assert!(ArtifactManifest::cache_path(&cfg)?.exists());
+        assert!(matches!(
+            setup_helper(2000, None, &cfg),
+            Err(Error::UnsupportedDegree { .. })
+        ));
+        // the cached manifest and parameters are enough offline
+        assert_eq!(setup_helper(1000, None, &cfg.clone().offline(true))?, pp);
+
+        // local directories as mirrors
+        let cache = tempfile::tempdir()?;
+        let cfg = cfg.cache_dir(cache.path()).mirrors([
+            "file:///nonexistent".into(),
+            format!("file://{}", mirror.path().display()),
+        ]);
+        assert_eq!(setup_helper(1000, None, &cfg)?, pp);
+
+        // a cached file corrupted since indexed is quarantined and fetched again
+        let cached = default_path(Some(cache.path().to_path_buf()), 1024)?;
+        let mtime = std::fs::metadata(&cached)?.modified()?;
+        let mut bytes = std::fs::read(&cached)?;
+        bytes[8 + 500 * 64] ^= 1;
+        std::fs::write(&cached, bytes)?;
+        std::fs::File::options()
+            .write(true)
+            .open(&cached)?
+            .set_modified(mtime)?;
+        assert_eq!(setup_helper(1000, None, &cfg)?, pp);
+        // with its index and record
+        assert_eq!(
+            std::fs::read_dir(QuarantineRecord::quarantine_dir(&cached))?.count(),
+            3
+        );
+
+        // compressed artifacts, on request
+        compress_aztec_srs(
+            artifact.clone(),
+            release.join("kzg10-aztec20-srs-1024-compressed.bin"),
+        )?;
+        let cache = tempfile::tempdir()?;
+        let compressed_cfg = cfg
+            .clone()
+            .cache_dir(cache.path())
+            .encoding(PointEncoding::Compressed)
+            .decompression(Decompression::Checked);
+        assert_eq!(setup_helper(1000, None, &compressed_cfg)?, pp);
+        assert!(compressed_path(Some(cache.path().to_path_buf()), 1024)?.exists());
+        assert!(!default_path(Some(cache.path().to_path_buf()), 1024)?.exists());
+
+        // artifacts not matching the manifest are rejected
+        let mut bytes = std::fs::read(&artifact)?;
+        bytes[100] ^= 1;
+        std::fs::write(&artifact, bytes)?;
+        let cache = tempfile::tempdir()?;
+        let cfg = cfg.cache_dir(cache.path());
+        assert!(matches!(
+            setup_helper(1000, None, &cfg),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_offline_prefetch() -> Result<()> {
+        let mirror = tempfile::tempdir()?;
+        let release = mirror.path().join(format!("v{RELEASE_VERSION}"));
+        std::fs::create_dir_all(&release)?;
+        std::fs::copy(
+            "data/aztec20/kzg10-aztec20-srs-1024.bin",
+            release.join("kzg10-aztec20-srs-1024.bin"),
+        )?;
+
+        let cache = tempfile::tempdir()?;
+        let cfg = SrsConfig::default()
+            .cache_dir(cache.path())
+            .mirrors([format!("file://{}", mirror.path().display())])
+            .offline(true);
+        let expected = default_path(Some(cache.path().to_path_buf()), 1024)?;
+        match setup_helper(1000, None, &cfg) {
+            Err(Error::NotCached { searched, .. }) => assert_eq!(searched, vec![expected.clone()]),
+            res => panic!("expect NotCached, got {res:?}"),
+        }
+        assert!(matches!(
+            prefetch_with_config(&[1000], &cfg),
+            Err(Error::NotCached { .. })
+        ));
+
+        let paths = prefetch_with_config(&[1000, 1024], &cfg.clone().offline(false))?;
+        assert_eq!(paths, vec![expected.clone(), expected.clone()]);
+        assert!(ChunkIndex::sidecar_path(&expected).exists());
+        assert_eq!(setup_helper(1000, None, &cfg)?.powers_of_g.len(), 1001);
+        assert!(matches!(
+            prefetch_with_config(&[0], &cfg),
+            Err(Error::DegreeOutOfRange { .. })
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_srs_download() {
         // Create a temporary project root
@@ -385,12 +914,18 @@ mod test {
 
         assert!(!path.exists());
 
+        // Offline setup fails if the file is not cached.
+        let cfg = SrsConfig::default().cache_dir(tempdir.path()).offline(true);
+        assert!(setup_helper(degree, None, &cfg).is_err());
+
         // Setup works if the file is not cached.
-        setup_helper(degree, path.clone()).unwrap();
+        let cfg = cfg.offline(false);
+        setup_helper(degree, None, &cfg).unwrap();
 
         assert!(path.exists());
 
-        // Setup works if the file is cached.
-        setup_helper(degree, path).unwrap();
+        // Setup works if the file is cached, even offline.
+        let cfg = cfg.offline(true).verification(VerificationLevel::Full);
+        setup_helper(degree, None, &cfg).unwrap();
     }
 }
@@ -0,0 +1,215 @@
+//! Aztec's ignition points as flattened by barretenberg and Noir's `bb`, i.e.
+//! the `bn254_g1.dat` and `bn254_g2.dat` files of their CRS directory
+//! (`~/.bb-crs` by default), downloaded from <https://crs.aztec.network>.
+//!
+//! Points are in the same big-endian limb encoding as the transcripts of
+//! [`aztec20`](crate::kzg10::aztec20), without any manifest: `bn254_g1.dat`
+//! holds the G1 powers of tau from the generator `[1]_1` onwards, possibly
+//! truncated to the number of points needed, and `bn254_g2.dat` only holds
+//! `x.[1]_2`.
+
+use ark_bn254::{Bn254, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_poly_commit::kzg10::UniversalParams;
+use ark_std::{
+    collections::BTreeMap,
+    format,
+    io::{Read, Write},
+    vec::Vec,
+};
+
+use crate::{
+    errors::{Error, Result},
+    kzg10::aztec20::{bytes_to_g2, g1_to_bytes, g2_to_bytes, read_g1_points, G1_SIZE, G2_SIZE},
+};
+
+/// Filename of the G1 points in the CRS directory of barretenberg
+pub const G1_BASENAME: &str = "bn254_g1.dat";
+/// Filename of the G2 point in the CRS directory of barretenberg
+pub const G2_BASENAME: &str = "bn254_g2.dat";
+
+/// The CRS of barretenberg: the powers of tau `g1` in G1, and `tau.[1]_2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BbCrs {
+    /// tau^i.[1]_1, starting from the generator
+    pub g1: Vec<G1Affine>,
+    /// tau.[1]_2
+    pub g2: G2Affine,
+}
+
+impl BbCrs {
+    /// Parse the first `num_points` G1 points of `bn254_g1.dat` from `g1`, and
+    /// the G2 point of `bn254_g2.dat` from `g2`. Every point read is checked to
+    /// be on the curve and in the prime order subgroup.
+    pub fn read<R1: Read, R2: Read>(g1: &mut R1, g2: &mut R2, num_points: usize) -> Result<Self> {
+        let g1 = read_g1_points(g1, num_points).map_err(|e| e.context(G1_BASENAME))?;
+        if g1.first().is_some_and(|g| *g != G1Affine::generator()) {
+            return Err(Error::MalformedTranscript(format!(
+                "{G1_BASENAME} doesn't start from the G1 generator"
+            )));
+        }
+        let mut bytes = [0u8; G2_SIZE];
+        g2.read_exact(&mut bytes)?;
+        let g2 = bytes_to_g2(&bytes).map_err(|e| e.context(G2_BASENAME))?;
+        Ok(Self { g1, g2 })
+    }
+
+    /// Build the CRS from KZG10 public parameters, e.g. from
+    /// [`aztec20::setup()`](crate::kzg10::aztec20::setup), keeping all of
+    /// their powers of tau.
+    ///
+    /// Fails unless `pp.h` is the G2 generator, as barretenberg assumes it.
+    pub fn from_universal_params(pp: &UniversalParams<Bn254>) -> Result<Self> {
+        if pp.h != G2Affine::generator() || pp.powers_of_g.first() != Some(&G1Affine::generator()) {
+            return Err(Error::InvalidSrs(
+                "barretenberg expects the generators as g and h".into(),
+            ));
+        }
+        Ok(Self {
+            g1: pp.powers_of_g.clone(),
+            g2: pp.beta_h,
+        })
+    }
+
+    /// Serialize the G1 points into `g1` and the G2 point into `g2`, the
+    /// inverse of [`BbCrs::read()`].
+    pub fn write<W1: Write, W2: Write>(&self, g1: &mut W1, g2: &mut W2) -> Result<()> {
+        for p in &self.g1 {
+            g1.write_all(&g1_to_bytes(p))?;
+        }
+        g2.write_all(&g2_to_bytes(&self.g2))?;
+        Ok(())
+    }
+
+    /// Number of G1 points of `bn254_g1.dat` of `file_size` bytes
+    pub fn num_points(file_size: u64) -> Result<usize> {
+        if !file_size.is_multiple_of(G1_SIZE as u64) {
+            return Err(Error::MalformedTranscript(format!(
+                "{G1_BASENAME} has {file_size} bytes, not a multiple of {G1_SIZE}"
+            )));
+        }
+        Ok((file_size / G1_SIZE as u64) as usize)
+    }
+
+    /// Convert into KZG10 public parameters supporting polynomials up to
+    /// `supported_degree`.
+    pub fn into_universal_params(self, supported_degree: usize) -> Result<UniversalParams<Bn254>> {
+        if !(1..self.g1.len()).contains(&supported_degree) {
+            return Err(Error::DegreeOutOfRange {
+                degree: supported_degree,
+                max: self.g1.len().saturating_sub(1),
+            });
+        }
+        let mut powers_of_g = self.g1;
+        powers_of_g.truncate(supported_degree + 1);
+        let h = G2Affine::generator();
+        Ok(UniversalParams {
+            powers_of_g,
+            // NOTE: used for hiding variant of KZG, not supported in Aztec's CRS.
+            powers_of_gamma_g: BTreeMap::new(),
+            h,
+            beta_h: self.g2,
+            // NOTE: not supported in Aztec's CRS.
+            neg_powers_of_h: BTreeMap::new(),
+            prepared_h: h.into(),
+            prepared_beta_h: self.g2.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        load::kzg10::bn254::{
+            aztec::{self, load_aztec_srs},
+            barretenberg::{export_aztec_srs, load_bb_srs_with_config, store_bb_srs},
+        },
+        SrsConfig, TrustPolicy,
+    };
+
+    #[test]
+    fn test_bb_crs() -> anyhow::Result<()> {
+        let pp = load_aztec_srs(1024, "data/aztec20/kzg10-aztec20-srs-1024.bin".into())?;
+        let crs = BbCrs::from_universal_params(&pp)?;
+        let (mut g1, mut g2) = (Vec::new(), Vec::new());
+        crs.write(&mut g1, &mut g2)?;
+        assert_eq!(BbCrs::num_points(g1.len() as u64)?, 1025);
+        assert_eq!(g2.len(), G2_SIZE);
+
+        // the same encoding as the transcripts, from the generator (1, 2)
+        assert_eq!(g1[..G1_SIZE], g1_to_bytes(&G1Affine::generator()));
+        assert_eq!(g1[7], 1);
+        assert_eq!(g1[32 + 7], 2);
+
+        let read = BbCrs::read(&mut g1.as_slice(), &mut g2.as_slice(), 1025)?;
+        assert_eq!(read, crs);
+        assert_eq!(read.clone().into_universal_params(1024)?, pp);
+        let prefix = BbCrs::read(&mut g1.as_slice(), &mut g2.as_slice(), 101)?;
+        assert_eq!(
+            prefix.into_universal_params(100)?.powers_of_g[..],
+            pp.powers_of_g[..101]
+        );
+        assert!(read.into_universal_params(1025).is_err());
+        assert!(BbCrs::read(&mut g1.as_slice(), &mut g2.as_slice(), 1026).is_err());
+        assert!(BbCrs::num_points(g1.len() as u64 - 1).is_err());
+
+        // not starting from the generator
+        assert!(BbCrs::read(&mut &g1[G1_SIZE..], &mut g2.as_slice(), 10).is_err());
+        // not an SRS of barretenberg
+        let mut other = pp.clone();
+        other.h = pp.beta_h;
+        assert!(BbCrs::from_universal_params(&other).is_err());
+
+        // from the cached file, as read by barretenberg
+        let dir = tempfile::tempdir()?;
+        let crs_dir = dir.path().join("bb-crs");
+        export_aztec_srs(
+            512,
+            "data/aztec20/kzg10-aztec20-srs-1024.bin".into(),
+            crs_dir.clone(),
+        )?;
+        assert_eq!(
+            std::fs::read(crs_dir.join(G1_BASENAME))?,
+            g1[..513 * G1_SIZE]
+        );
+        assert_eq!(std::fs::read(crs_dir.join(G2_BASENAME))?, g2);
+        // with the points of the pinned artifact, served from the cache
+        let cache = tempfile::tempdir()?;
+        let cached = aztec::default_path(Some(cache.path().to_path_buf()), 1024)?;
+        std::fs::create_dir_all(cached.parent().unwrap())?;
+        std::fs::copy("data/aztec20/kzg10-aztec20-srs-1024.bin", &cached)?;
+        let cfg = SrsConfig::default().cache_dir(cache.path()).offline(true);
+        let loaded = load_bb_srs_with_config(300, crs_dir.clone(), &cfg)?;
+        assert_eq!(loaded.powers_of_g[..], pp.powers_of_g[..301]);
+        assert_eq!(loaded.beta_h, pp.beta_h);
+        assert!(load_bb_srs_with_config(513, crs_dir.clone(), &cfg).is_err());
+
+        // other points are only accepted if trusted, and then checked
+        let mut forged = crs.clone();
+        forged.g1.truncate(301);
+        forged.g1[300] = forged.g1[299];
+        let forged_dir = dir.path().join("forged");
+        store_bb_srs(
+            &BbCrs::into_universal_params(forged, 300)?,
+            forged_dir.clone(),
+        )?;
+        assert!(matches!(
+            load_bb_srs_with_config(300, forged_dir.clone(), &cfg),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            load_bb_srs_with_config(
+                300,
+                forged_dir.clone(),
+                &cfg.clone().trust(TrustPolicy::Custom)
+            ),
+            Err(Error::InvalidSrs(_))
+        ));
+        assert_eq!(
+            load_bb_srs_with_config(300, crs_dir, &cfg.trust(TrustPolicy::Custom))?,
+            loaded
+        );
+        Ok(())
+    }
+}
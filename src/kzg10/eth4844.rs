@@ -0,0 +1,314 @@
+//! Ethereum's KZG ceremony for EIP-4844 (proto-danksharding), 4096 BLS12-381
+//! G1 points and 65 G2 points in total.
+//! For concrete details: <https://github.com/ethereum/kzg-ceremony-specs>
+
+use std::path::PathBuf;
+
+use ark_bls12_381::{Bls12_381, G1Affine, G2Affine};
+use ark_poly_commit::kzg10::UniversalParams;
+use ark_serialize::CanonicalDeserialize;
+use ark_std::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+use serde::Deserialize;
+
+use crate::{
+    config::offline_from_env,
+    constants::ETH4844_URL,
+    errors::{Error, Result},
+    load::{
+        fetch_file,
+        kzg10::bls12_381::eth4844::{default_path, load_eth4844_srs},
+    },
+};
+
+const NUM_G1: usize = 4096;
+const NUM_G2: usize = 65;
+const G1_COMPRESSED_SIZE: usize = 48;
+const G2_COMPRESSED_SIZE: usize = 96;
+
+/// Full output of the EIP-4844 ceremony, all points are in their compressed
+/// zcash encoding in the published files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedSetup {
+    /// [1], tau.[1], ..., tau^4095.[1]
+    pub g1_monomial: Vec<G1Affine>,
+    /// L_i(tau).[1] over the 4096-th roots of unity, in bit-reversed order
+    pub g1_lagrange: Vec<G1Affine>,
+    /// [1]_2, tau.[1]_2, ..., tau^64.[1]_2
+    pub g2_monomial: Vec<G2Affine>,
+}
+
+// Layout of `trusted_setup_4096.json` from the consensus-specs, points are
+// `0x`-prefixed hex strings.
+#[derive(Deserialize)]
+struct TrustedSetupJson {
+    g1_monomial: Vec<String>,
+    g1_lagrange: Vec<String>,
+    g2_monomial: Vec<String>,
+}
+
+impl TrustedSetup {
+    /// Parse the `trusted_setup.txt` format used by `c-kzg-4844`: the number
+    /// of G1 and G2 points on the first two lines, followed by G1 points in
+    /// Lagrange form, G2 points in monomial form, then G1 points in monomial
+    /// form, one hex-encoded point per line.
+    pub fn from_txt(bytes: &[u8]) -> Result<Self> {
+        let content = core::str::from_utf8(bytes)
+            .map_err(|_| malformed("trusted setup is not valid utf8".into()))?;
+        let mut lines = content.lines().map(str::trim).filter(|l| !l.is_empty());
+
+        let mut next_count = |what: &str| -> Result<usize> {
+            lines
+                .next()
+                .ok_or_else(|| malformed(format!("missing number of {what} points")))?
+                .parse::<usize>()
+                .map_err(|_| malformed(format!("invalid number of {what} points")))
+        };
+        let num_g1 = next_count("G1")?;
+        let num_g2 = next_count("G2")?;
+        if num_g1 != NUM_G1 || num_g2 != NUM_G2 {
+            return Err(malformed(format!(
+                "Expect {NUM_G1} G1 and {NUM_G2} G2 points, got {num_g1} and {num_g2}"
+            )));
+        }
+
+        let g1_lagrange =
+            parse_points::<G1Affine>(lines.by_ref().take(num_g1), G1_COMPRESSED_SIZE)?;
+        let g2_monomial =
+            parse_points::<G2Affine>(lines.by_ref().take(num_g2), G2_COMPRESSED_SIZE)?;
+        let g1_monomial =
+            parse_points::<G1Affine>(lines.by_ref().take(num_g1), G1_COMPRESSED_SIZE)?;
+        if lines.next().is_some() {
+            return Err(malformed(
+                "Unexpected trailing data in trusted setup".into(),
+            ));
+        }
+
+        Self::new(g1_monomial, g1_lagrange, g2_monomial)
+    }
+
+    /// Parse the `trusted_setup_4096.json` format used by the consensus-specs.
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        let json: TrustedSetupJson =
+            serde_json::from_slice(bytes).map_err(|e| malformed(format!("{e}")))?;
+        Self::new(
+            parse_points(
+                json.g1_monomial.iter().map(String::as_str),
+                G1_COMPRESSED_SIZE,
+            )?,
+            parse_points(
+                json.g1_lagrange.iter().map(String::as_str),
+                G1_COMPRESSED_SIZE,
+            )?,
+            parse_points(
+                json.g2_monomial.iter().map(String::as_str),
+                G2_COMPRESSED_SIZE,
+            )?,
+        )
+    }
+
+    fn new(
+        g1_monomial: Vec<G1Affine>,
+        g1_lagrange: Vec<G1Affine>,
+        g2_monomial: Vec<G2Affine>,
+    ) -> Result<Self> {
+        if g1_monomial.len() != NUM_G1 {
+            return Err(malformed(format!(
+                "Expect {NUM_G1} G1 points in monomial form, got {}",
+                g1_monomial.len()
+            )));
+        }
+        if g1_lagrange.len() != NUM_G1 {
+            return Err(malformed(format!(
+                "Expect {NUM_G1} G1 points in Lagrange form, got {}",
+                g1_lagrange.len()
+            )));
+        }
+        if g2_monomial.len() != NUM_G2 {
+            return Err(malformed(format!(
+                "Expect {NUM_G2} G2 points, got {}",
+                g2_monomial.len()
+            )));
+        }
+        Ok(Self {
+            g1_monomial,
+            g1_lagrange,
+            g2_monomial,
+        })
+    }
+
+    /// Convert into KZG10 public parameters supporting polynomials up to
+    /// `supported_degree`.
+    pub fn into_universal_params(
+        mut self,
+        supported_degree: usize,
+    ) -> Result<UniversalParams<Bls12_381>> {
+        if !(1..NUM_G1).contains(&supported_degree) {
+            return Err(Error::DegreeOutOfRange {
+                degree: supported_degree,
+                max: NUM_G1 - 1,
+            });
+        }
+        self.g1_monomial.truncate(supported_degree + 1);
+
+        let h = self.g2_monomial[0];
+        let beta_h = self.g2_monomial[1];
+        Ok(UniversalParams {
+            powers_of_g: self.g1_monomial,
+            // NOTE: used for hiding variant of KZG, not supported in the ceremony.
+            powers_of_gamma_g: BTreeMap::new(),
+            h,
+            beta_h,
+            // NOTE: not supported in the ceremony.
+            neg_powers_of_h: BTreeMap::new(),
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+        })
+    }
+}
+
+fn malformed(msg: String) -> Error {
+    Error::MalformedTranscript(msg)
+}
+
+// Decode hex-encoded compressed points, with or without the `0x` prefix.
+fn parse_points<'a, P: CanonicalDeserialize>(
+    lines: impl Iterator<Item = &'a str>,
+    point_size: usize,
+) -> Result<Vec<P>> {
+    lines
+        .map(|line| {
+            let bytes = hex::decode(line.trim_start_matches("0x"))
+                .map_err(|_| malformed(format!("Invalid hex-encoded point: {line}")))?;
+            if bytes.len() != point_size {
+                return Err(malformed(format!(
+                    "Expect {point_size} bytes per point, got {}",
+                    bytes.len()
+                )));
+            }
+            // includes on-curve and subgroup checks
+            P::deserialize_compressed(&bytes[..])
+                .map_err(|e| Error::InvalidPoint(format!("{e}: {line}")))
+        })
+        .collect()
+}
+
+/// Retreive public parameters when given as input the maximum degree degree for
+/// the polynomial commitment scheme, at most 4095.
+/// This API is similar to [KZG10::setup][setup]
+///
+/// # Locally cached ceremony file
+/// The `trusted_setup.txt` is downloaded from `c-kzg-4844` on first use, you
+/// can point to your own copy via `ETH4844_SRS_PATH` (see `.env` file).
+/// With `ARK_SRS_OFFLINE` set, a missing file is an [`Error::NotCached`]
+/// instead.
+///
+/// [setup]: https://docs.rs/ark-poly-commit/0.4.0/ark_poly_commit/kzg10/struct.KZG10.html#method.setup
+pub fn setup(supported_degree: usize) -> Result<UniversalParams<Bls12_381>> {
+    load_trusted_setup()?.into_universal_params(supported_degree)
+}
+
+/// Load the full ceremony output, including the G1 points in Lagrange form
+/// and all 65 G2 powers. See details from [`setup()`].
+pub fn load_trusted_setup() -> Result<TrustedSetup> {
+    let param_file = match std::env::var("ETH4844_SRS_PATH") {
+        Ok(path) => {
+            tracing::info!("Using ETH4844_SRS_PATH {path}");
+            PathBuf::from(path)
+        },
+        Err(_) => default_path(None)?,
+    };
+    setup_helper(param_file, offline_from_env())
+}
+
+// Setup helper to allow passing param_file for tests because setting
+// environment variables is prone to errors because they are shared by all the
+// tests.
+fn setup_helper(param_file: PathBuf, offline: bool) -> Result<TrustedSetup> {
+    if !param_file.exists() {
+        tracing::info!("Trusted setup file {param_file:?} does not exist");
+        if offline {
+            let hint = format!("download {ETH4844_URL} to it, or point ETH4844_SRS_PATH to a copy");
+            return Err(Error::not_cached(vec![param_file], hint));
+        }
+        fetch_file(ETH4844_URL, &param_file)?;
+    } else {
+        tracing::info!("Trusted setup file already exists.");
+    }
+    // a mismatching file is moved into quarantine, never removed
+    load_eth4844_srs(param_file)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{constants::ETH4844_CHECKSUMS, load::QuarantineRecord};
+    use anyhow::Result;
+    use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+    use ark_serialize::CanonicalSerialize;
+    use std::fs;
+
+    #[test]
+    fn test_eth4844_trusted_setup() -> Result<()> {
+        let ts = setup_helper(PathBuf::from("data/eth4844/trusted_setup.txt"), false)?;
+
+        // all powers share the same tau
+        assert_eq!(ts.g1_monomial[0], G1Affine::generator());
+        assert_eq!(ts.g2_monomial[0], G2Affine::generator());
+        assert_eq!(
+            Bls12_381::pairing(ts.g1_monomial[1], ts.g2_monomial[0]),
+            Bls12_381::pairing(ts.g1_monomial[0], ts.g2_monomial[1]),
+        );
+        // Lagrange basis polynomials sum up to 1
+        let sum: ark_bls12_381::G1Projective = ts.g1_lagrange.iter().sum();
+        assert_eq!(sum.into_affine(), G1Affine::generator());
+
+        let pp = ts.clone().into_universal_params(100)?;
+        assert_eq!(pp.powers_of_g.len(), 101);
+        assert_eq!(pp.beta_h, ts.g2_monomial[1]);
+        assert!(ts.clone().into_universal_params(NUM_G1).is_err());
+
+        // the same points in the consensus-specs json format
+        fn to_hex<P: CanonicalSerialize>(points: &[P]) -> Vec<String> {
+            points
+                .iter()
+                .map(|p| {
+                    let mut bytes = Vec::new();
+                    p.serialize_compressed(&mut bytes).unwrap();
+                    format!("0x{}", hex::encode(bytes))
+                })
+                .collect()
+        }
+        let json = serde_json::json!({
+            "g1_monomial": to_hex(&ts.g1_monomial),
+            "g1_lagrange": to_hex(&ts.g1_lagrange),
+            "g2_monomial": to_hex(&ts.g2_monomial),
+        });
+        assert_eq!(TrustedSetup::from_json(&serde_json::to_vec(&json)?)?, ts);
+
+        // a file failing its checksum is moved into quarantine, not removed
+        let dir = tempfile::tempdir()?;
+        let tampered = dir.path().join("trusted_setup.txt");
+        let mut bytes = fs::read("data/eth4844/trusted_setup.txt")?;
+        bytes[10] ^= 1;
+        fs::write(&tampered, &bytes)?;
+        assert!(matches!(
+            setup_helper(tampered.clone(), true),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(!tampered.exists());
+        let quarantine = QuarantineRecord::quarantine_dir(&tampered);
+        let moved = fs::read_dir(&quarantine)?
+            .map(|entry| entry.map(|e| e.path()))
+            .find(|p| {
+                p.as_ref()
+                    .is_ok_and(|p| p.extension() != Some("json".as_ref()))
+            })
+            .unwrap()?;
+        assert_eq!(fs::read(&moved)?, bytes);
+        let record: QuarantineRecord =
+            serde_json::from_slice(&fs::read(QuarantineRecord::sidecar_path(&moved))?)?;
+        assert_eq!(record.expected, hex::encode(ETH4844_CHECKSUMS[0].1));
+        assert_eq!(record.source, tampered);
+        Ok(())
+    }
+}
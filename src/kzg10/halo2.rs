@@ -0,0 +1,483 @@
+//! KZG parameters of halo2 (PSE, Axiom and Scroll forks), as the serialized
+//! `ParamsKZG<Bn256>` of `halo2_proofs`, e.g. the `kzg_bn254_{k}.srs` files
+//! derived from Perpetual Powers of Tau, and exported from any BN254 SRS.
+//! For concrete details: <https://github.com/privacy-scaling-explorations/halo2/blob/main/halo2_backend/src/poly/kzg/commitment.rs>
+
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::{short_weierstrass::SWCurveConfig, AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, MontFp, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_poly_commit::kzg10::UniversalParams;
+use ark_std::{
+    cfg_chunks,
+    collections::BTreeMap,
+    format,
+    io::{Read, Seek, SeekFrom, Write},
+    vec,
+    vec::Vec,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    errors::{Error, Result},
+    kzg10::ppot::{bytes_to_bigint, bytes_to_g1, bytes_to_g2, N8},
+};
+
+/// Largest `k` of the 2^k evaluation domains of BN254's scalar field
+pub const MAX_K: u32 = 28;
+
+/// The primitive 2^28-th root of unity used by `halo2curves` for BN254, which
+/// fixes the order of the Lagrange basis. It differs from the one of
+/// `ark_bn254::Fr`, being derived from the multiplicative generator 7 instead
+/// of 5.
+pub const HALO2_ROOT_OF_UNITY: Fr =
+    MontFp!("1748695177688661943023146337482803886740723238769601073607632802312037301404");
+
+/// Point encodings of `halo2_proofs::SerdeFormat`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SerdeFormat {
+    /// Compressed points: the canonical little-endian x coordinate, with the
+    /// parity of y in the top bit. Identity is all zeros.
+    Processed,
+    /// Uncompressed points, as the little-endian Montgomery form of their
+    /// coordinates, as written by `ParamsKZG::write()`. Files written as
+    /// `RawBytesUnchecked` are the same bytes.
+    #[default]
+    RawBytes,
+}
+
+impl SerdeFormat {
+    /// Size of an encoded G1 point
+    pub fn g1_size(&self) -> usize {
+        match self {
+            Self::Processed => N8,
+            Self::RawBytes => 2 * N8,
+        }
+    }
+
+    /// Size of an encoded G2 point
+    pub fn g2_size(&self) -> usize {
+        2 * self.g1_size()
+    }
+}
+
+/// `ParamsKZG<Bn256>` of halo2: `k` (a `u32` little-endian), then the `2^k`
+/// powers of tau `g`, their Lagrange basis `g_lagrange` over the 2^k domain,
+/// `g2` and `s_g2 = tau.g2`.
+///
+/// `g` is possibly truncated to a prefix, and `g_lagrange` left out, see
+/// [`ParamsKzg::read()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamsKzg {
+    /// log2 of the number of powers of tau
+    pub k: u32,
+    /// tau^i.g1, for i < 2^k
+    pub g: Vec<G1Affine>,
+    /// L_i(tau).g1, for i < 2^k, `L_i` being the Lagrange polynomials of the
+    /// domain generated by [`HALO2_ROOT_OF_UNITY`]^(2^(28-k)). Empty if left
+    /// out.
+    pub g_lagrange: Vec<G1Affine>,
+    /// generator of G2
+    pub g2: G2Affine,
+    /// tau.g2
+    pub s_g2: G2Affine,
+}
+
+impl ParamsKzg {
+    /// Parse params written in `format`, reading at most `max_points` of `g`
+    /// (all of them if `None`), and `g_lagrange` only if `lagrange`, as these
+    /// files grow up to tens of GB. Every point read is checked to be on the
+    /// curve and in the prime order subgroup.
+    pub fn read<R: Read + Seek>(
+        r: &mut R,
+        format: SerdeFormat,
+        max_points: Option<usize>,
+        lagrange: bool,
+    ) -> Result<Self> {
+        r.seek(SeekFrom::Start(0))?;
+        let mut k = [0u8; 4];
+        r.read_exact(&mut k)?;
+        let k = u32::from_le_bytes(k);
+        if k > MAX_K {
+            return Err(Error::MalformedTranscript(format!(
+                "Invalid k = {k} of halo2 params, at most {MAX_K}"
+            )));
+        }
+        let n = 1usize << k;
+        let (g1_size, g2_size) = (format.g1_size() as u64, format.g2_size() as u64);
+        let g_pos = 4;
+        let lagrange_pos = g_pos + n as u64 * g1_size;
+        let g2_pos = lagrange_pos + n as u64 * g1_size;
+        let file_size = r.seek(SeekFrom::End(0))?;
+        if file_size != g2_pos + 2 * g2_size {
+            return Err(Error::MalformedTranscript(format!(
+                "Expect {} bytes of halo2 params for k = {k}, got {file_size}",
+                g2_pos + 2 * g2_size
+            )));
+        }
+
+        let g = read_g1_points(r, g_pos, max_points.map_or(n, |m| m.min(n)), format)?;
+        let g_lagrange = if lagrange {
+            read_g1_points(r, lagrange_pos, n, format)?
+        } else {
+            Vec::new()
+        };
+        r.seek(SeekFrom::Start(g2_pos))?;
+        let mut bytes = vec![0u8; 2 * g2_size as usize];
+        r.read_exact(&mut bytes)?;
+        let (g2, s_g2) = bytes.split_at(g2_size as usize);
+        Ok(Self {
+            k,
+            g,
+            g_lagrange,
+            g2: decode_g2(g2, format)?,
+            s_g2: decode_g2(s_g2, format)?,
+        })
+    }
+
+    /// Build halo2 params of `2^k` powers from KZG10 public parameters of
+    /// another ceremony, e.g. from [`aztec20::setup()`](crate::kzg10::aztec20::setup),
+    /// computing their Lagrange basis over the 2^k domain of halo2.
+    ///
+    /// Needs at least `2^k` powers of tau in G1, i.e. a degree of `2^k - 1`,
+    /// extra points are dropped.
+    pub fn from_universal_params(pp: &UniversalParams<Bn254>, k: u32) -> Result<Self> {
+        if k > MAX_K {
+            return Err(Error::InvalidSrs(format!(
+                "Invalid k = {k}, at most {MAX_K}"
+            )));
+        }
+        let n = 1usize << k;
+        if pp.powers_of_g.len() < n {
+            return Err(Error::DegreeOutOfRange {
+                degree: n - 1,
+                max: pp.powers_of_g.len().saturating_sub(1),
+            });
+        }
+        let g = pp.powers_of_g[..n].to_vec();
+        Ok(Self {
+            k,
+            g_lagrange: lagrange_basis(&g, k),
+            g,
+            g2: pp.h,
+            s_g2: pp.beta_h,
+        })
+    }
+
+    /// Serialize in `format`, the inverse of [`ParamsKzg::read()`].
+    ///
+    /// # Note
+    /// Fails if `g` is truncated or `g_lagrange` left out (e.g. read with
+    /// `max_points`), as the file would be malformed.
+    pub fn write<W: Write>(&self, w: &mut W, format: SerdeFormat) -> Result<()> {
+        let n = 1usize << self.k;
+        if self.k > MAX_K || self.g.len() != n || self.g_lagrange.len() != n {
+            return Err(Error::InvalidSrs(format!(
+                "Incomplete halo2 params for k = {}",
+                self.k
+            )));
+        }
+        w.write_all(&self.k.to_le_bytes())?;
+        for p in self.g.iter().chain(&self.g_lagrange) {
+            w.write_all(&encode_g1(p, format))?;
+        }
+        w.write_all(&encode_g2(&self.g2, format))?;
+        w.write_all(&encode_g2(&self.s_g2, format))?;
+        Ok(())
+    }
+
+    /// Convert into KZG10 public parameters supporting polynomials up to
+    /// `supported_degree`.
+    pub fn into_universal_params(self, supported_degree: usize) -> Result<UniversalParams<Bn254>> {
+        Ok(self.into_parts(supported_degree)?.0)
+    }
+
+    /// Same as [`ParamsKzg::into_universal_params()`], also returning the
+    /// Lagrange basis `g_lagrange`, empty if it wasn't read.
+    pub fn into_parts(
+        self,
+        supported_degree: usize,
+    ) -> Result<(UniversalParams<Bn254>, Vec<G1Affine>)> {
+        if !(1..self.g.len()).contains(&supported_degree) {
+            return Err(Error::DegreeOutOfRange {
+                degree: supported_degree,
+                max: self.g.len().saturating_sub(1),
+            });
+        }
+        let mut powers_of_g = self.g;
+        powers_of_g.truncate(supported_degree + 1);
+        let pp = UniversalParams {
+            powers_of_g,
+            // NOTE: used for hiding variant of KZG, not supported in the params.
+            powers_of_gamma_g: BTreeMap::new(),
+            h: self.g2,
+            beta_h: self.s_g2,
+            // NOTE: not supported in the params.
+            neg_powers_of_h: BTreeMap::new(),
+            prepared_h: self.g2.into(),
+            prepared_beta_h: self.s_g2.into(),
+        };
+        Ok((pp, self.g_lagrange))
+    }
+}
+
+/// The generator of the 2^k domain of halo2, see [`HALO2_ROOT_OF_UNITY`]
+pub fn halo2_omega(k: u32) -> Fr {
+    HALO2_ROOT_OF_UNITY.pow([1u64 << (MAX_K - k)])
+}
+
+// L_i(tau).g1 from the powers tau^j.g1: the inverse FFT of the powers over
+// the 2^k domain of halo2, whose order must match the one of halo2
+fn lagrange_basis(g: &[G1Affine], k: u32) -> Vec<G1Affine> {
+    let mut domain = Radix2EvaluationDomain::<Fr>::new(1 << k).expect("2^k is a valid domain");
+    domain.group_gen = halo2_omega(k);
+    domain.group_gen_inv = domain
+        .group_gen
+        .inverse()
+        .expect("a root of unity is invertible");
+    let mut points: Vec<G1Projective> = g.iter().map(|p| p.into_group()).collect();
+    domain.ifft_in_place(&mut points);
+    G1Projective::normalize_batch(&points)
+}
+
+fn read_g1_points<R: Read + Seek>(
+    r: &mut R,
+    pos: u64,
+    n: usize,
+    format: SerdeFormat,
+) -> Result<Vec<G1Affine>> {
+    let mut bytes = vec![0u8; n * format.g1_size()];
+    r.seek(SeekFrom::Start(pos))?;
+    r.read_exact(&mut bytes)?;
+    cfg_chunks!(bytes, format.g1_size())
+        .enumerate()
+        .map(|(i, b)| decode_g1(b, format).map_err(|e| e.context(format_args!("G1 point {i}"))))
+        .collect()
+}
+
+fn decode_g1(bytes: &[u8], format: SerdeFormat) -> Result<G1Affine> {
+    match format {
+        SerdeFormat::RawBytes => bytes_to_g1(bytes),
+        SerdeFormat::Processed => {
+            let (x, odd) = decompress_coordinate(bytes)?;
+            let Some(x) = x else {
+                return Ok(G1Affine::identity());
+            };
+            let y2 = x.square() * x + ark_bn254::g1::Config::COEFF_B;
+            let y = y2.sqrt().ok_or_else(|| {
+                Error::InvalidPoint("Compressed G1 point not on the curve".into())
+            })?;
+            let y = if is_odd(&y) == odd { y } else { -y };
+            // BN254's G1 has a cofactor of 1
+            Ok(G1Affine::new_unchecked(x, y))
+        },
+    }
+}
+
+fn decode_g2(bytes: &[u8], format: SerdeFormat) -> Result<G2Affine> {
+    match format {
+        SerdeFormat::RawBytes => bytes_to_g2(bytes),
+        SerdeFormat::Processed => {
+            let (c1, odd) = decompress_coordinate(&bytes[N8..])?;
+            let (c0, _) = decompress_coordinate(&bytes[..N8])?;
+            if c0.is_none() && c1.is_none() {
+                return Ok(G2Affine::identity());
+            }
+            let x = Fq2::new(c0.unwrap_or_default(), c1.unwrap_or_default());
+            let y2 = x.square() * x + ark_bn254::g2::Config::COEFF_B;
+            let y = y2.sqrt().ok_or_else(|| {
+                Error::InvalidPoint("Compressed G2 point not on the curve".into())
+            })?;
+            let y = if is_odd(&y.c0) == odd { y } else { -y };
+            let point = G2Affine::new_unchecked(x, y);
+            if !point.is_in_correct_subgroup_assuming_on_curve() {
+                return Err(Error::InvalidPoint(
+                    "G2 point not in the prime order subgroup".into(),
+                ));
+            }
+            Ok(point)
+        },
+    }
+}
+
+// The canonical little-endian coordinate (`None` if zero) and the flag in its
+// top bit
+fn decompress_coordinate(bytes: &[u8]) -> Result<(Option<Fq>, bool)> {
+    let mut repr = [0u8; N8];
+    repr.copy_from_slice(bytes);
+    let flag = repr[N8 - 1] >> 7 == 1;
+    repr[N8 - 1] &= 0x7f;
+    let x = Fq::from_bigint(bytes_to_bigint(&repr))
+        .ok_or_else(|| Error::InvalidPoint("Field element is not reduced".into()))?;
+    Ok(((!x.is_zero()).then_some(x), flag))
+}
+
+// Inverse of `decode_g1()`
+fn encode_g1(p: &G1Affine, format: SerdeFormat) -> Vec<u8> {
+    match format {
+        SerdeFormat::RawBytes => crate::kzg10::ppot::g1_to_bytes(p),
+        SerdeFormat::Processed => match p.xy() {
+            None => vec![0u8; N8],
+            Some((x, y)) => compress_coordinate(&x, is_odd(&y)),
+        },
+    }
+}
+
+// Inverse of `decode_g2()`
+fn encode_g2(p: &G2Affine, format: SerdeFormat) -> Vec<u8> {
+    match format {
+        SerdeFormat::RawBytes => crate::kzg10::ppot::g2_to_bytes(p),
+        SerdeFormat::Processed => match p.xy() {
+            None => vec![0u8; 2 * N8],
+            Some((x, y)) => [
+                compress_coordinate(&x.c0, false),
+                compress_coordinate(&x.c1, is_odd(&y.c0)),
+            ]
+            .concat(),
+        },
+    }
+}
+
+fn compress_coordinate(x: &Fq, flag: bool) -> Vec<u8> {
+    let mut bytes = x.into_bigint().to_bytes_le();
+    bytes[N8 - 1] |= (flag as u8) << 7;
+    bytes
+}
+
+fn is_odd(f: &Fq) -> bool {
+    f.into_bigint().is_odd()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        load::kzg10::bn254::{
+            aztec::load_aztec_srs,
+            halo2::{load_halo2_params, store_halo2_params},
+        },
+        verify::kzg10::{same_tau_as, verify_powers},
+    };
+    use ark_ec::VariableBaseMSM;
+    use ark_ff::UniformRand;
+    use ark_std::{io::Cursor, path::PathBuf};
+
+    // params of 2^k powers of a known tau, Lagrange basis included
+    fn mock_params(k: u32, tau: Fr) -> ParamsKzg {
+        let n = 1usize << k;
+        let g = G1Affine::generator();
+        let g2 = G2Affine::generator();
+        // L_i(tau) = omega^i (tau^n - 1) / (n (tau - omega^i))
+        let omega = halo2_omega(k);
+        let z = tau.pow([n as u64]) - Fr::ONE;
+        let n_inv = Fr::from(n as u64).inverse().unwrap();
+        ParamsKzg {
+            k,
+            g: (0..n)
+                .map(|i| (g * tau.pow([i as u64])).into_affine())
+                .collect(),
+            g_lagrange: (0..n)
+                .map(|i| {
+                    let w = omega.pow([i as u64]);
+                    let l = w * z * n_inv * (tau - w).inverse().unwrap();
+                    (g * l).into_affine()
+                })
+                .collect(),
+            g2,
+            s_g2: (g2 * tau).into_affine(),
+        }
+    }
+
+    fn to_bytes(params: &ParamsKzg, format: SerdeFormat) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        params.write(&mut bytes, format).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_halo2_params_import() -> anyhow::Result<()> {
+        assert_eq!(halo2_omega(MAX_K).pow([1u64 << (MAX_K - 1)]), -Fr::ONE);
+        let tau = Fr::rand(&mut ark_std::test_rng());
+        let params = mock_params(4, tau);
+        for format in [SerdeFormat::RawBytes, SerdeFormat::Processed] {
+            let bytes = to_bytes(&params, format);
+            assert_eq!(
+                bytes.len(),
+                4 + 32 * format.g1_size() + 2 * format.g2_size()
+            );
+            // serialization is the exact inverse
+            let parsed = ParamsKzg::read(&mut Cursor::new(&bytes), format, None, true)?;
+            assert_eq!(parsed, params);
+
+            let truncated = ParamsKzg::read(&mut Cursor::new(&bytes), format, Some(9), false)?;
+            assert!(truncated.write(&mut Vec::new(), format).is_err());
+            let (pp, lagrange) = truncated.into_parts(8)?;
+            assert!(lagrange.is_empty());
+            assert_eq!(pp.powers_of_g, params.g[..9]);
+            verify_powers(&pp)?;
+
+            // corrupted files are rejected
+            assert!(ParamsKzg::read(&mut Cursor::new(&bytes[1..]), format, None, true).is_err());
+            let mut bad = bytes.clone();
+            bad[5] ^= 0x10;
+            assert!(ParamsKzg::read(&mut Cursor::new(&bad), format, None, false).is_err());
+        }
+
+        // only params of the same tau are compatible
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("kzg_bn254_4.srs");
+        std::fs::write(&path, to_bytes(&params, SerdeFormat::RawBytes))?;
+        let pp = load_halo2_params(path, SerdeFormat::RawBytes, None, false)?
+            .into_universal_params(15)?;
+        let aztec = load_aztec_srs(
+            1024,
+            PathBuf::from("data/aztec20/kzg10-aztec20-srs-1024.bin"),
+        )?;
+        assert!(same_tau_as(&pp, &pp));
+        assert!(!same_tau_as(&pp, &aztec));
+        let other = mock_params(3, tau).into_universal_params(7)?;
+        assert!(same_tau_as(&pp, &other));
+        Ok(())
+    }
+
+    #[test]
+    fn test_halo2_params_export() -> anyhow::Result<()> {
+        // the Lagrange basis is in the order of halo2's domain
+        let tau = Fr::rand(&mut ark_std::test_rng());
+        let params = mock_params(4, tau);
+        let pp = params.clone().into_universal_params(15)?;
+        assert_eq!(ParamsKzg::from_universal_params(&pp, 4)?, params);
+        assert!(ParamsKzg::from_universal_params(&pp, 5).is_err());
+
+        let aztec = load_aztec_srs(
+            1024,
+            PathBuf::from("data/aztec20/kzg10-aztec20-srs-1024.bin"),
+        )?;
+        let dir = tempfile::tempdir()?;
+        for format in [SerdeFormat::RawBytes, SerdeFormat::Processed] {
+            let path = dir.path().join("kzg_bn254_10.srs");
+            store_halo2_params(&aztec, 10, path.clone(), format)?;
+            let params = load_halo2_params(path, format, None, true)?;
+            assert_eq!(params.k, 10);
+            let (pp, g_lagrange) = params.into_parts(1023)?;
+            assert_eq!(pp.powers_of_g, aztec.powers_of_g[..1024]);
+            assert!(same_tau_as(&pp, &aztec));
+
+            // committing to evaluations equals committing to the coefficients
+            // they interpolate
+            let rng = &mut ark_std::test_rng();
+            let evals: Vec<Fr> = (0..1024).map(|_| Fr::rand(rng)).collect();
+            let mut domain = Radix2EvaluationDomain::<Fr>::new(1024).unwrap();
+            domain.group_gen = halo2_omega(10);
+            domain.group_gen_inv = domain.group_gen.inverse().unwrap();
+            let coeffs = domain.ifft(&evals);
+            assert_eq!(
+                G1Projective::msm(&g_lagrange, &evals).unwrap(),
+                G1Projective::msm(&pp.powers_of_g, &coeffs).unwrap(),
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,851 @@
+//! Perpetual Powers of Tau ceremony, as distributed by Hermez in snarkjs
+//! `.ptau` files, up to 2^28 powers of tau over BN254.
+//! For concrete details: <https://github.com/iden3/snarkjs#7-prepare-phase-2>
+
+use std::path::PathBuf;
+
+use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, BigInteger256, PrimeField};
+use ark_poly_commit::kzg10::UniversalParams;
+use ark_std::{
+    collections::BTreeMap,
+    format,
+    io::{Read, Seek, SeekFrom, Write},
+    string::String,
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    config::SrsConfig,
+    errors::{Error, Result},
+    load::{
+        fetch_file,
+        kzg10::bn254::ppot::{default_path, load_ppot_srs_with},
+    },
+};
+
+/// Magic bytes at the start of every `.ptau` file
+pub const PTAU_MAGIC: &[u8; 4] = b"ptau";
+const PTAU_VERSION: u32 = 1;
+
+/// Section id of the header
+pub const SECTION_HEADER: u32 = 1;
+/// Section id of tau^i.[1]
+pub const SECTION_TAU_G1: u32 = 2;
+/// Section id of tau^i.[1]_2
+pub const SECTION_TAU_G2: u32 = 3;
+/// Section id of alpha.tau^i.[1]
+pub const SECTION_ALPHA_TAU_G1: u32 = 4;
+/// Section id of beta.tau^i.[1]
+pub const SECTION_BETA_TAU_G1: u32 = 5;
+/// Section id of beta.[1]_2
+pub const SECTION_BETA_G2: u32 = 6;
+
+/// Size of a base field element in bytes
+pub(crate) const N8: usize = 32;
+pub(crate) const G1_SIZE: usize = 2 * N8;
+pub(crate) const G2_SIZE: usize = 4 * N8;
+
+/// Smallest and largest `power` of the files published by Hermez
+const MIN_POWER: u32 = 1;
+const MAX_POWER: u32 = 28;
+const HERMEZ_URL: &str = "https://storage.googleapis.com/zkevm/ptau";
+
+/// Content of section 1 of a `.ptau` file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtauHeader {
+    /// the file supports circuits of up to 2^power constraints
+    pub power: u32,
+    /// the power of the whole ceremony the file is derived from
+    pub ceremony_power: u32,
+}
+
+impl PtauHeader {
+    /// Number of points in the tauG1 section: tau^0 to tau^(2^(power+1)-2)
+    pub fn num_tau_g1(&self) -> usize {
+        (1usize << self.power) * 2 - 1
+    }
+
+    /// Number of points in the tauG2, alphaTauG1 and betaTauG1 sections
+    pub fn num_tau_g2(&self) -> usize {
+        1usize << self.power
+    }
+}
+
+/// Phase-1 sections of a `.ptau` file over BN254.
+///
+/// Power sections are possibly truncated to a prefix, see [`Ptau::read()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ptau {
+    /// section 1
+    pub header: PtauHeader,
+    /// section 2: tau^i.[1]
+    pub tau_g1: Vec<G1Affine>,
+    /// section 3: tau^i.[1]_2
+    pub tau_g2: Vec<G2Affine>,
+    /// section 4: alpha.tau^i.[1]
+    pub alpha_tau_g1: Vec<G1Affine>,
+    /// section 5: beta.tau^i.[1]
+    pub beta_tau_g1: Vec<G1Affine>,
+    /// section 6: beta.[1]_2
+    pub beta_g2: G2Affine,
+}
+
+impl Ptau {
+    /// Parse a `.ptau` file, reading at most `max_points` from each of the
+    /// power sections (all of them if `None`), as these files can grow up to
+    /// tens of GB.
+    pub fn read<R: Read + Seek>(r: &mut R, max_points: Option<usize>) -> Result<Self> {
+        let sections = read_section_table(r)?;
+        let find = |id: u32| find_section(&sections, id);
+
+        let (pos, size) = find(SECTION_HEADER)?;
+        r.seek(SeekFrom::Start(pos))?;
+        let header = read_header(r, size)?;
+
+        let cap = |n: usize| max_points.map_or(n, |m| m.min(n));
+        let (n1, n2) = (header.num_tau_g1(), header.num_tau_g2());
+        let pos = section_pos(find(SECTION_TAU_G1)?, n1, G1_SIZE)?;
+        let tau_g1 = read_g1_points(r, pos, cap(n1))?;
+        let pos = section_pos(find(SECTION_TAU_G2)?, n2, G2_SIZE)?;
+        // `[1]_2` and `tau.[1]_2` are always needed for the KZG10 parameters
+        let tau_g2 = read_g2_points(r, pos, cap(n2).max(n2.min(2)))?;
+        let pos = section_pos(find(SECTION_ALPHA_TAU_G1)?, n2, G1_SIZE)?;
+        let alpha_tau_g1 = read_g1_points(r, pos, cap(n2))?;
+        let pos = section_pos(find(SECTION_BETA_TAU_G1)?, n2, G1_SIZE)?;
+        let beta_tau_g1 = read_g1_points(r, pos, cap(n2))?;
+        let pos = section_pos(find(SECTION_BETA_G2)?, 1, G2_SIZE)?;
+        let beta_g2 = read_g2_points(r, pos, 1)?[0];
+
+        Ok(Self {
+            header,
+            tau_g1,
+            tau_g2,
+            alpha_tau_g1,
+            beta_tau_g1,
+            beta_g2,
+        })
+    }
+
+    /// Serialize into the `.ptau` format, the inverse of [`Ptau::read()`].
+    ///
+    /// # Note
+    /// Fails if the power sections are truncated (e.g. read with
+    /// `max_points`), as the file would be malformed.
+    pub fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        let (n1, n2) = (self.header.num_tau_g1(), self.header.num_tau_g2());
+        if self.tau_g1.len() != n1
+            || self.tau_g2.len() != n2
+            || self.alpha_tau_g1.len() != n2
+            || self.beta_tau_g1.len() != n2
+        {
+            return Err(Error::InvalidSrs(format!(
+                "Incomplete powers of tau for power {}",
+                self.header.power
+            )));
+        }
+        write_sections(
+            w,
+            vec![
+                (SECTION_HEADER, header_to_bytes(&self.header)),
+                (
+                    SECTION_TAU_G1,
+                    self.tau_g1.iter().flat_map(g1_to_bytes).collect(),
+                ),
+                (
+                    SECTION_TAU_G2,
+                    self.tau_g2.iter().flat_map(g2_to_bytes).collect(),
+                ),
+                (
+                    SECTION_ALPHA_TAU_G1,
+                    self.alpha_tau_g1.iter().flat_map(g1_to_bytes).collect(),
+                ),
+                (
+                    SECTION_BETA_TAU_G1,
+                    self.beta_tau_g1.iter().flat_map(g1_to_bytes).collect(),
+                ),
+                (SECTION_BETA_G2, g2_to_bytes(&self.beta_g2)),
+            ],
+        )
+    }
+
+    /// Convert into KZG10 public parameters supporting polynomials up to
+    /// `supported_degree`.
+    pub fn into_universal_params(self, supported_degree: usize) -> Result<UniversalParams<Bn254>> {
+        match self.tau_g2[..] {
+            [h, beta_h, ..] => to_universal_params(self.tau_g1, h, beta_h, supported_degree),
+            _ => Err(Error::MalformedTranscript(
+                "ptau file with fewer than 2 tauG2 points".into(),
+            )),
+        }
+    }
+}
+
+/// Powers of tau of another ceremony (e.g. Aztec's ignition) in the container
+/// format of `.ptau` files, but **not** a snarkjs `.ptau` file.
+///
+/// Only sections 1 to 3 are written, and the tauG2 section only holds `[1]_2`
+/// and `tau.[1]_2` since no other G2 power is known. Such a file is rejected by
+/// `snarkjs powersoftau verify`, by Groth16 setups and by [`Ptau::read()`]:
+/// it's only meant for tools reading nothing but the first two tauG2 points,
+/// such as the PLONK setup of snarkjs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialPtau {
+    /// section 1, whose `ceremony_power` is `power`
+    pub header: PtauHeader,
+    /// section 2: tau^i.[1]
+    pub tau_g1: Vec<G1Affine>,
+    /// section 3, truncated to `[1]_2` and `tau.[1]_2`
+    pub tau_g2: [G2Affine; 2],
+}
+
+impl PartialPtau {
+    /// Build from KZG10 public parameters of another ceremony, e.g. from
+    /// [`aztec20::setup()`](crate::kzg10::aztec20::setup).
+    ///
+    /// The `power` is the largest one whose `2^(power+1) - 1` powers of tau
+    /// in G1 are all available, extra points are dropped.
+    pub fn from_universal_params(pp: &UniversalParams<Bn254>) -> Result<Self> {
+        let power = (1..=MAX_POWER)
+            .rev()
+            .find(|p| (1usize << p) * 2 - 1 <= pp.powers_of_g.len())
+            .ok_or_else(|| Error::InvalidSrs("Need at least 3 powers of tau in G1".into()))?;
+        let header = PtauHeader {
+            power,
+            ceremony_power: power,
+        };
+        Ok(Self {
+            header,
+            tau_g1: pp.powers_of_g[..header.num_tau_g1()].to_vec(),
+            tau_g2: [pp.h, pp.beta_h],
+        })
+    }
+
+    /// Parse a file written by [`PartialPtau::write()`], reading at most
+    /// `max_points` of the tauG1 section (all of them if `None`).
+    pub fn read<R: Read + Seek>(r: &mut R, max_points: Option<usize>) -> Result<Self> {
+        let sections = read_section_table(r)?;
+        if sections.len() != 3 {
+            return Err(Error::MalformedTranscript(format!(
+                "Expect only sections 1 to 3 in a partial ptau file, got {}",
+                sections.len()
+            )));
+        }
+        let find = |id: u32| find_section(&sections, id);
+
+        let (pos, size) = find(SECTION_HEADER)?;
+        r.seek(SeekFrom::Start(pos))?;
+        let header = read_header(r, size)?;
+
+        let n1 = header.num_tau_g1();
+        let pos = section_pos(find(SECTION_TAU_G1)?, n1, G1_SIZE)?;
+        let tau_g1 = read_g1_points(r, pos, max_points.map_or(n1, |m| m.min(n1)))?;
+        let pos = section_pos(find(SECTION_TAU_G2)?, 2, G2_SIZE)?;
+        let tau_g2 = read_g2_points(r, pos, 2)?;
+
+        Ok(Self {
+            header,
+            tau_g1,
+            tau_g2: [tau_g2[0], tau_g2[1]],
+        })
+    }
+
+    /// Serialize sections 1 to 3, the inverse of [`PartialPtau::read()`].
+    ///
+    /// # Note
+    /// Fails if the tauG1 section is truncated (e.g. read with
+    /// `max_points`), as the file would be malformed.
+    pub fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        if self.tau_g1.len() != self.header.num_tau_g1() {
+            return Err(Error::InvalidSrs(format!(
+                "Incomplete powers of tau for power {}",
+                self.header.power
+            )));
+        }
+        write_sections(
+            w,
+            vec![
+                (SECTION_HEADER, header_to_bytes(&self.header)),
+                (
+                    SECTION_TAU_G1,
+                    self.tau_g1.iter().flat_map(g1_to_bytes).collect(),
+                ),
+                (
+                    SECTION_TAU_G2,
+                    self.tau_g2.iter().flat_map(g2_to_bytes).collect(),
+                ),
+            ],
+        )
+    }
+
+    /// Convert into KZG10 public parameters supporting polynomials up to
+    /// `supported_degree`.
+    pub fn into_universal_params(self, supported_degree: usize) -> Result<UniversalParams<Bn254>> {
+        let [h, beta_h] = self.tau_g2;
+        to_universal_params(self.tau_g1, h, beta_h, supported_degree)
+    }
+}
+
+fn to_universal_params(
+    mut powers_of_g: Vec<G1Affine>,
+    h: G2Affine,
+    beta_h: G2Affine,
+    supported_degree: usize,
+) -> Result<UniversalParams<Bn254>> {
+    if !(1..powers_of_g.len()).contains(&supported_degree) {
+        return Err(Error::DegreeOutOfRange {
+            degree: supported_degree,
+            max: powers_of_g.len().saturating_sub(1),
+        });
+    }
+    powers_of_g.truncate(supported_degree + 1);
+    Ok(UniversalParams {
+        powers_of_g,
+        // NOTE: used for hiding variant of KZG, not supported in the ceremony.
+        powers_of_gamma_g: BTreeMap::new(),
+        h,
+        beta_h,
+        // NOTE: not supported in the ceremony.
+        neg_powers_of_h: BTreeMap::new(),
+        prepared_h: h.into(),
+        prepared_beta_h: beta_h.into(),
+    })
+}
+
+fn find_section(sections: &[(u32, u64, u64)], id: u32) -> Result<(u64, u64)> {
+    sections
+        .iter()
+        .find(|(t, ..)| *t == id)
+        .map(|(_, pos, size)| (*pos, *size))
+        .ok_or_else(|| Error::MalformedTranscript(format!("Missing section {id} in ptau file")))
+}
+
+fn header_to_bytes(header: &PtauHeader) -> Vec<u8> {
+    let mut bytes = (N8 as u32).to_le_bytes().to_vec();
+    bytes.extend_from_slice(&Fq::MODULUS.to_bytes_le());
+    bytes.extend_from_slice(&header.power.to_le_bytes());
+    bytes.extend_from_slice(&header.ceremony_power.to_le_bytes());
+    bytes
+}
+
+fn write_sections<W: Write>(w: &mut W, sections: Vec<(u32, Vec<u8>)>) -> Result<()> {
+    w.write_all(PTAU_MAGIC)?;
+    w.write_all(&PTAU_VERSION.to_le_bytes())?;
+    w.write_all(&(sections.len() as u32).to_le_bytes())?;
+    for (id, data) in sections {
+        w.write_all(&id.to_le_bytes())?;
+        w.write_all(&(data.len() as u64).to_le_bytes())?;
+        w.write_all(&data)?;
+    }
+    Ok(())
+}
+
+// Returns `(section_id, data_pos, data_size)` of all sections in the file.
+fn read_section_table<R: Read + Seek>(r: &mut R) -> Result<Vec<(u32, u64, u64)>> {
+    let end = r.seek(SeekFrom::End(0))?;
+    r.seek(SeekFrom::Start(0))?;
+    let mut magic = [0u8; 4];
+    r.read_exact(&mut magic)?;
+    if &magic != PTAU_MAGIC {
+        return Err(Error::MalformedTranscript(
+            "Not a ptau file, invalid magic bytes".into(),
+        ));
+    }
+    let version = read_u32(r)?;
+    if version != PTAU_VERSION {
+        return Err(Error::MalformedTranscript(format!(
+            "Unsupported ptau version {version}"
+        )));
+    }
+    let num_sections = read_u32(r)?;
+
+    let mut sections = Vec::new();
+    for _ in 0..num_sections {
+        let id = read_u32(r)?;
+        let size = read_u64(r)?;
+        let pos = r.stream_position()?;
+        // sizes are read from the file, don't let them overflow
+        if pos
+            .checked_add(size)
+            .is_none_or(|section_end| section_end > end)
+        {
+            return Err(Error::MalformedTranscript(format!(
+                "Truncated ptau file, section {id} of {size} bytes"
+            )));
+        }
+        sections.push((id, pos, size));
+        r.seek(SeekFrom::Start(pos + size))?;
+    }
+    if let Some(id) = sections
+        .iter()
+        .map(|(id, ..)| id)
+        .find(|id| sections.iter().filter(|(other, ..)| other == *id).count() > 1)
+    {
+        return Err(Error::MalformedTranscript(format!(
+            "Duplicated section {id} in ptau file"
+        )));
+    }
+    Ok(sections)
+}
+
+fn read_header<R: Read>(r: &mut R, size: u64) -> Result<PtauHeader> {
+    if size != 4 + N8 as u64 + 4 + 4 {
+        return Err(Error::MalformedTranscript(format!(
+            "Unexpected ptau header size {size}"
+        )));
+    }
+    let n8 = read_u32(r)? as usize;
+    if n8 != N8 {
+        return Err(Error::MalformedTranscript(format!(
+            "Unsupported field element size {n8}, only BN254 is supported"
+        )));
+    }
+    let mut q = [0u8; N8];
+    r.read_exact(&mut q)?;
+    if bytes_to_bigint(&q) != Fq::MODULUS {
+        return Err(Error::MalformedTranscript(
+            "Unsupported curve, only BN254 is supported".into(),
+        ));
+    }
+    let power = read_u32(r)?;
+    let ceremony_power = read_u32(r)?;
+    if power == 0 || power > ceremony_power || ceremony_power > MAX_POWER {
+        return Err(Error::MalformedTranscript(format!(
+            "Invalid power {power} of a ceremony of power {ceremony_power}"
+        )));
+    }
+    Ok(PtauHeader {
+        power,
+        ceremony_power,
+    })
+}
+
+// Check the section size and returns its position.
+fn section_pos((pos, size): (u64, u64), num_points: usize, point_size: usize) -> Result<u64> {
+    if size != (num_points * point_size) as u64 {
+        return Err(Error::MalformedTranscript(format!(
+            "Expect {num_points} points of {point_size} bytes in section, got {size} bytes"
+        )));
+    }
+    Ok(pos)
+}
+
+fn read_g1_points<R: Read + Seek>(r: &mut R, pos: u64, n: usize) -> Result<Vec<G1Affine>> {
+    r.seek(SeekFrom::Start(pos))?;
+    let mut buf = [0u8; G1_SIZE];
+    (0..n)
+        .map(|_| {
+            r.read_exact(&mut buf)?;
+            bytes_to_g1(&buf)
+        })
+        .collect()
+}
+
+fn read_g2_points<R: Read + Seek>(r: &mut R, pos: u64, n: usize) -> Result<Vec<G2Affine>> {
+    r.seek(SeekFrom::Start(pos))?;
+    let mut buf = [0u8; G2_SIZE];
+    (0..n)
+        .map(|_| {
+            r.read_exact(&mut buf)?;
+            bytes_to_g2(&buf)
+        })
+        .collect()
+}
+
+fn read_u32<R: Read>(r: &mut R) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+pub(crate) fn bytes_to_bigint(bytes: &[u8]) -> BigInteger256 {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(chunk);
+        *limb = u64::from_le_bytes(buf);
+    }
+    BigInteger256::new(limbs)
+}
+
+// snarkjs stores field elements little-endian in Montgomery form, same as the
+// internal representation of arkworks.
+fn bytes_to_fq(bytes: &[u8]) -> Result<Fq> {
+    let repr = bytes_to_bigint(bytes);
+    if repr >= Fq::MODULUS {
+        return Err(Error::InvalidPoint("Field element is not reduced".into()));
+    }
+    Ok(Fq::new_unchecked(repr))
+}
+
+// little-endian Montgomery form, the inverse of `bytes_to_fq()`
+fn fq_to_bytes(f: &Fq) -> Vec<u8> {
+    f.0.to_bytes_le()
+}
+
+pub(crate) fn g1_to_bytes(p: &G1Affine) -> Vec<u8> {
+    if p.is_zero() {
+        return vec![0u8; G1_SIZE];
+    }
+    [fq_to_bytes(&p.x), fq_to_bytes(&p.y)].concat()
+}
+
+pub(crate) fn g2_to_bytes(p: &G2Affine) -> Vec<u8> {
+    if p.is_zero() {
+        return vec![0u8; G2_SIZE];
+    }
+    [p.x.c0, p.x.c1, p.y.c0, p.y.c1]
+        .iter()
+        .flat_map(fq_to_bytes)
+        .collect()
+}
+
+pub(crate) fn bytes_to_g1(bytes: &[u8]) -> Result<G1Affine> {
+    // snarkjs encodes the point at infinity as all zeros
+    if bytes.iter().all(|b| *b == 0) {
+        return Ok(G1Affine::identity());
+    }
+    let point = G1Affine::new_unchecked(bytes_to_fq(&bytes[..N8])?, bytes_to_fq(&bytes[N8..])?);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidPoint(
+            "G1 point not on curve or not in the prime order subgroup".into(),
+        ));
+    }
+    Ok(point)
+}
+
+pub(crate) fn bytes_to_g2(bytes: &[u8]) -> Result<G2Affine> {
+    if bytes.iter().all(|b| *b == 0) {
+        return Ok(G2Affine::identity());
+    }
+    let [x_c0, x_c1, y_c0, y_c1] = [0, 1, 2, 3].map(|i| bytes_to_fq(&bytes[i * N8..(i + 1) * N8]));
+    let point = G2Affine::new_unchecked(Fq2::new(x_c0?, x_c1?), Fq2::new(y_c0?, y_c1?));
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidPoint(
+            "G2 point not on curve or not in the prime order subgroup".into(),
+        ));
+    }
+    Ok(point)
+}
+
+/// Returns the smallest `power` of the Hermez files that supports
+/// `supported_degree`.
+pub fn power_for_degree(supported_degree: usize) -> Result<u32> {
+    (MIN_POWER..=MAX_POWER)
+        .find(|p| (1usize << p) * 2 - 1 > supported_degree)
+        .ok_or(Error::DegreeOutOfRange {
+            degree: supported_degree,
+            max: (1usize << MAX_POWER) * 2 - 2,
+        })
+}
+
+/// Returns the download URL of Hermez's `.ptau` file for `power`
+pub fn hermez_url(power: u32) -> String {
+    format!(
+        "{HERMEZ_URL}/{}",
+        crate::load::kzg10::bn254::ppot::basename(power)
+    )
+}
+
+/// Retreive public parameters when given as input the maximum degree degree for
+/// the polynomial commitment scheme.
+/// This API is similar to [KZG10::setup][setup]
+///
+/// # Locally cached ptau file
+/// Unless `PPOT_SRS_PATH` points to a local `.ptau` file, the smallest
+/// `powersOfTau28_hez_final_{power}.ptau` covering `supported_degree` is
+/// downloaded from Hermez on first use, unless `ARK_SRS_OFFLINE` is set in
+/// which case [`Error::NotCached`] is returned.
+///
+/// # Note
+/// The whole file is checked against the BLAKE2b hash pinned for its power in
+/// [`PPOT_BLAKE2B_CHECKSUMS`](crate::constants::PPOT_BLAKE2B_CHECKSUMS), and moved into quarantine if it doesn't match.
+/// Files of powers without pinned hash are rejected with
+/// [`Error::UntrustedFile`], see [`setup_with_config()`] to trust them.
+///
+/// [setup]: https://docs.rs/ark-poly-commit/0.4.0/ark_poly_commit/kzg10/struct.KZG10.html#method.setup
+pub fn setup(supported_degree: usize) -> Result<UniversalParams<Bn254>> {
+    setup_with_config(supported_degree, &SrsConfig::default())
+}
+
+/// Same as [`setup()`], but the cache location, offline mode and trust policy
+/// are taken from `cfg` instead of the defaults. With
+/// [`TrustPolicy::Custom`](crate::config::TrustPolicy::Custom),
+/// files of powers without pinned hash are accepted once all the points loaded
+/// pass [`verify_powers()`](crate::verify::kzg10::verify_powers).
+pub fn setup_with_config(
+    supported_degree: usize,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<Bn254>> {
+    let (param_file, url) = match std::env::var("PPOT_SRS_PATH") {
+        Ok(path) => {
+            tracing::info!("Using PPOT_SRS_PATH {path}");
+            (PathBuf::from(path), None)
+        },
+        Err(_) => {
+            let power = power_for_degree(supported_degree)?;
+            (
+                default_path(Some(cfg.cache_root()?), power)?,
+                Some(hermez_url(power)),
+            )
+        },
+    };
+    if !param_file.exists() {
+        tracing::info!("SRS file {param_file:?} does not exist");
+        let url = match url {
+            Some(url) if !cfg.is_offline() => url,
+            Some(url) => {
+                let hint = format!("download {url} to it, or point PPOT_SRS_PATH to a copy");
+                return Err(Error::not_cached(vec![param_file], hint));
+            },
+            None => {
+                return Err(Error::not_cached(
+                    vec![param_file],
+                    "PPOT_SRS_PATH must point to an existing .ptau file",
+                ))
+            },
+        };
+        fetch_file(&url, &param_file)?;
+    }
+    load_ppot_srs_with(supported_degree, param_file, cfg.trust)?
+        .into_universal_params(supported_degree)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        config::TrustPolicy,
+        load::{
+            kzg10::bn254::{
+                aztec::load_aztec_srs,
+                ppot::{basename, load_ppot_srs_with},
+            },
+            QuarantineRecord,
+        },
+        verify::kzg10::verify_powers,
+    };
+    use ark_bn254::Fr;
+    use ark_ec::{pairing::Pairing, CurveGroup};
+    use ark_ff::{Field, UniformRand};
+    use ark_std::io::Cursor;
+    use std::fs;
+
+    fn section(id: u32, data: Vec<u8>) -> Vec<u8> {
+        [
+            id.to_le_bytes().to_vec(),
+            (data.len() as u64).to_le_bytes().to_vec(),
+            data,
+        ]
+        .concat()
+    }
+
+    // a well-formed ptau file of `power` from a known toxic waste
+    fn mock_ptau(power: u32) -> (Vec<u8>, Fr) {
+        let rng = &mut ark_std::test_rng();
+        let [tau, alpha, beta] = [(); 3].map(|_| Fr::rand(rng));
+        let g = G1Affine::generator();
+        let h = G2Affine::generator();
+        let powers: Vec<Fr> = (0..(1usize << power) * 2 - 1)
+            .scan(Fr::from(1u64), |acc, _| {
+                let cur = *acc;
+                *acc *= tau;
+                Some(cur)
+            })
+            .collect();
+        let n = 1usize << power;
+
+        let mut header = (N8 as u32).to_le_bytes().to_vec();
+        header.extend_from_slice(&Fq::MODULUS.to_bytes_le());
+        header.extend_from_slice(&power.to_le_bytes());
+        header.extend_from_slice(&power.to_le_bytes());
+
+        let sections = [
+            section(SECTION_HEADER, header),
+            section(
+                SECTION_TAU_G1,
+                powers
+                    .iter()
+                    .flat_map(|t| g1_to_bytes(&(g * t).into_affine()))
+                    .collect(),
+            ),
+            section(
+                SECTION_TAU_G2,
+                powers[..n]
+                    .iter()
+                    .flat_map(|t| g2_to_bytes(&(h * t).into_affine()))
+                    .collect(),
+            ),
+            section(
+                SECTION_ALPHA_TAU_G1,
+                powers[..n]
+                    .iter()
+                    .flat_map(|t| g1_to_bytes(&(g * (alpha * t)).into_affine()))
+                    .collect(),
+            ),
+            section(
+                SECTION_BETA_TAU_G1,
+                powers[..n]
+                    .iter()
+                    .flat_map(|t| g1_to_bytes(&(g * (beta * t)).into_affine()))
+                    .collect(),
+            ),
+            section(SECTION_BETA_G2, g2_to_bytes(&(h * beta).into_affine())),
+        ];
+        let mut bytes = PTAU_MAGIC.to_vec();
+        bytes.extend_from_slice(&PTAU_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(sections.len() as u32).to_le_bytes());
+        bytes.extend(sections.concat());
+        (bytes, tau)
+    }
+
+    #[test]
+    fn test_ptau_parsing() -> Result<()> {
+        let (bytes, tau) = mock_ptau(3);
+        let ptau = Ptau::read(&mut Cursor::new(&bytes), None)?;
+        assert_eq!(ptau.header.power, 3);
+        assert_eq!(ptau.tau_g1.len(), 15);
+        assert_eq!(ptau.tau_g2.len(), 8);
+        assert_eq!(
+            ptau.tau_g2[7],
+            (G2Affine::generator() * tau.pow([7])).into_affine()
+        );
+        assert_eq!(
+            Bn254::pairing(ptau.alpha_tau_g1[1], ptau.tau_g2[0]),
+            Bn254::pairing(ptau.alpha_tau_g1[0], ptau.tau_g2[1]),
+        );
+        assert_eq!(
+            Bn254::pairing(ptau.beta_tau_g1[0], ptau.tau_g2[0]),
+            Bn254::pairing(ptau.tau_g1[0], ptau.beta_g2),
+        );
+
+        let pp = Ptau::read(&mut Cursor::new(&bytes), Some(4))?.into_universal_params(3)?;
+        assert_eq!(pp.powers_of_g.len(), 4);
+        assert_eq!(
+            pp.powers_of_g[3],
+            (G1Affine::generator() * tau.pow([3])).into_affine()
+        );
+        assert_eq!(pp.beta_h, ptau.tau_g2[1]);
+
+        // corrupted files are rejected
+        let mut bad = bytes.clone();
+        bad[0] = b'x';
+        assert!(Ptau::read(&mut Cursor::new(&bad), None).is_err());
+        let mut bad = bytes.clone();
+        let last = bad.len() - 1;
+        bad[last] ^= 1;
+        assert!(Ptau::read(&mut Cursor::new(&bad), None).is_err());
+        assert!(Ptau::read(&mut Cursor::new(&bytes[..bytes.len() - 1]), None).is_err());
+
+        // serialization is the exact inverse
+        let mut written = Vec::new();
+        ptau.write(&mut written)?;
+        assert_eq!(written, bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ptau_export_aztec() -> Result<()> {
+        let pp = load_aztec_srs(
+            1024,
+            PathBuf::from("data/aztec20/kzg10-aztec20-srs-1024.bin"),
+        )?;
+        let ptau = PartialPtau::from_universal_params(&pp)?;
+        // 2^10 - 1 <= 1025 < 2^11 - 1
+        assert_eq!(ptau.header.power, 9);
+        assert_eq!(ptau.tau_g1[..], pp.powers_of_g[..1023]);
+
+        let mut bytes = Vec::new();
+        ptau.write(&mut bytes)?;
+        let parsed = PartialPtau::read(&mut Cursor::new(&bytes), None)?;
+        assert_eq!(parsed, ptau);
+        assert_eq!(parsed.tau_g2, [pp.h, pp.beta_h]);
+
+        let recovered = parsed.into_universal_params(1000)?;
+        assert_eq!(recovered.powers_of_g[..], pp.powers_of_g[..1001]);
+        assert_eq!(recovered.beta_h, pp.beta_h);
+
+        // not a snarkjs ptau file, and vice versa
+        assert!(Ptau::read(&mut Cursor::new(&bytes), None).is_err());
+        let (genuine, _) = mock_ptau(1);
+        assert!(PartialPtau::read(&mut Cursor::new(&genuine), None).is_err());
+
+        // truncated sections can't be exported
+        let truncated = PartialPtau::read(&mut Cursor::new(&bytes), Some(10))?;
+        assert!(truncated.write(&mut Vec::new()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hermez_ptau() -> Result<()> {
+        // the genuine file published by Hermez, pinned by its BLAKE2b hash
+        let src = PathBuf::from("data/ppot").join(basename(8));
+        let ptau = load_ppot_srs_with(1000, src.clone(), TrustPolicy::Pinned)?;
+        assert_eq!(ptau.header.power, 8);
+        assert_eq!(ptau.header.ceremony_power, 28);
+        assert_eq!(ptau.tau_g1.len(), 511);
+        let pp = ptau.into_universal_params(510)?;
+        verify_powers(&pp)?;
+        assert_eq!(pp.powers_of_g[0], G1Affine::generator());
+        assert_eq!(pp.h, G2Affine::generator());
+        assert_eq!(power_for_degree(510)?, 8);
+        assert_eq!(power_for_degree(1)?, 1);
+        // degree 0 is out of range, even with a single G1 point read
+        let ptau = load_ppot_srs_with(0, src.clone(), TrustPolicy::Pinned)?;
+        assert_eq!(ptau.tau_g2.len(), 2);
+        assert!(matches!(
+            ptau.into_universal_params(0),
+            Err(Error::DegreeOutOfRange { .. })
+        ));
+
+        // files of other powers are only accepted if trusted, and then checked
+        let dir = tempfile::tempdir()?;
+        let (bytes, _) = mock_ptau(3);
+        let custom = dir.path().join(basename(3));
+        fs::write(&custom, &bytes)?;
+        assert!(matches!(
+            load_ppot_srs_with(10, custom.clone(), TrustPolicy::Pinned),
+            Err(Error::UntrustedFile { .. })
+        ));
+        assert_eq!(
+            load_ppot_srs_with(10, custom.clone(), TrustPolicy::Custom)?
+                .tau_g1
+                .len(),
+            11
+        );
+
+        // section sizes overflowing the file
+        for size in [u64::MAX - 5, i64::MAX as u64] {
+            let mut bad = bytes.clone();
+            bad[16..24].copy_from_slice(&size.to_le_bytes());
+            assert!(matches!(
+                Ptau::read(&mut ark_std::io::Cursor::new(&bad), None),
+                Err(Error::MalformedTranscript(_))
+            ));
+        }
+
+        // a tampered file is moved into quarantine
+        let tampered = dir.path().join(basename(8));
+        let mut bytes = fs::read(&src)?;
+        *bytes.last_mut().unwrap() ^= 1;
+        fs::write(&tampered, &bytes)?;
+        assert!(matches!(
+            load_ppot_srs_with(10, tampered.clone(), TrustPolicy::Custom),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(!tampered.exists());
+        assert_eq!(
+            fs::read_dir(QuarantineRecord::quarantine_dir(&tampered))?.count(),
+            2
+        );
+        Ok(())
+    }
+}
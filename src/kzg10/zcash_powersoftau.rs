@@ -0,0 +1,706 @@
+//! Zcash's Powers of Tau ceremony for Sapling, 2^21 powers of tau in G2 (and
+//! twice as many in G1) over BLS12-381, as the `challenge`/`response` files of
+//! the `powersoftau` implementation, each starting with the BLAKE2b hash of the
+//! file it was computed from.
+//! For concrete details: <https://github.com/ebfull/powersoftau>
+
+use std::path::{Path, PathBuf};
+
+use ark_bls12_381::{Bls12_381, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_poly_commit::kzg10::UniversalParams;
+use ark_serialize::{CanonicalSerialize, Compress, Validate};
+use ark_std::{
+    cfg_chunks,
+    collections::BTreeMap,
+    format,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    string::ToString,
+    vec,
+    vec::Vec,
+};
+use blake2::{Blake2b512, Digest};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    ceremony::{self, Ceremony},
+    config::SrsConfig,
+    constants::{ZCASH_POWERSOFTAU_CHECKSUMS, ZCASH_POWERSOFTAU_TRANSCRIPT_HASHES},
+    errors::{Error, Result},
+    load::{CurveId, PointEncoding},
+    manifest::{Artifact, ZCASH_POWERSOFTAU},
+    verify::kzg10::verify_powers,
+    TrustPolicy,
+};
+
+/// Number of powers of tau in G2 of the Sapling ceremony, there are twice as
+/// many minus one in G1
+pub const TAU_POWERS_LENGTH: usize = 1 << 21;
+/// Max degree supported by the Sapling ceremony
+pub const MAX_DEGREE: usize = 2 * TAU_POWERS_LENGTH - 2;
+/// Size of the BLAKE2b hash at the start of every file
+pub const HASH_SIZE: usize = 64;
+
+const G1_UNCOMPRESSED_SIZE: usize = 96;
+const G1_COMPRESSED_SIZE: usize = 48;
+const G2_UNCOMPRESSED_SIZE: usize = 192;
+const G2_COMPRESSED_SIZE: usize = 96;
+/// Size of a contributor's [`PublicKey`] at the end of a `response` file
+pub const PUBLIC_KEY_SIZE: usize = 6 * G1_UNCOMPRESSED_SIZE + 3 * G2_UNCOMPRESSED_SIZE;
+
+/// The two kinds of files of the ceremony, alternating: each participant reads
+/// a `challenge` and writes a `response`, from which the coordinator computes
+/// the next `challenge`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptKind {
+    /// Hash of the previous `response` (or of nothing for the first one), then
+    /// the accumulator with uncompressed points
+    Challenge,
+    /// Hash of the `challenge`, then the accumulator with compressed points
+    /// and the [`PublicKey`] of the contribution
+    Response,
+}
+
+impl TranscriptKind {
+    /// Filename used by the `powersoftau` tools
+    pub fn basename(&self) -> &'static str {
+        match self {
+            Self::Challenge => "challenge",
+            Self::Response => "response",
+        }
+    }
+
+    fn compress(&self) -> Compress {
+        match self {
+            Self::Challenge => Compress::No,
+            Self::Response => Compress::Yes,
+        }
+    }
+
+    fn point_sizes(&self) -> (u64, u64) {
+        match self {
+            Self::Challenge => (G1_UNCOMPRESSED_SIZE as u64, G2_UNCOMPRESSED_SIZE as u64),
+            Self::Response => (G1_COMPRESSED_SIZE as u64, G2_COMPRESSED_SIZE as u64),
+        }
+    }
+
+    fn public_key_size(&self) -> u64 {
+        match self {
+            Self::Challenge => 0,
+            Self::Response => PUBLIC_KEY_SIZE as u64,
+        }
+    }
+
+    /// Expected size of a file of `tau_powers_length` powers of tau in G2
+    pub fn file_size(&self, tau_powers_length: usize) -> u64 {
+        let (g1, g2) = self.point_sizes();
+        let n = tau_powers_length as u64;
+        HASH_SIZE as u64 + (4 * n - 1) * g1 + (n + 1) * g2 + self.public_key_size()
+    }
+
+    /// Number of powers of tau in G2 of a file of `file_size` bytes, as the
+    /// format has no header. Ceremonies of any size are accepted, not only
+    /// [`TAU_POWERS_LENGTH`].
+    pub fn tau_powers_length(&self, file_size: u64) -> Result<usize> {
+        let (g1, g2) = self.point_sizes();
+        let fixed = HASH_SIZE as u64 + g2 + self.public_key_size();
+        let n = (file_size + g1).saturating_sub(fixed) / (4 * g1 + g2);
+        if n < 2 || self.file_size(n as usize) != file_size {
+            return Err(Error::MalformedTranscript(format!(
+                "{file_size} bytes is not the size of a {} file",
+                self.basename()
+            )));
+        }
+        Ok(n as usize)
+    }
+}
+
+/// Public key of a contribution, proving knowledge of its tau, alpha and beta,
+/// with uncompressed points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey {
+    /// (s, s.tau) in G1
+    pub tau_g1: (G1Affine, G1Affine),
+    /// (s, s.alpha) in G1
+    pub alpha_g1: (G1Affine, G1Affine),
+    /// (s, s.beta) in G1
+    pub beta_g1: (G1Affine, G1Affine),
+    /// r.tau in G2, `r` being derived from the G1 points
+    pub tau_g2: G2Affine,
+    /// r.alpha in G2
+    pub alpha_g2: G2Affine,
+    /// r.beta in G2
+    pub beta_g2: G2Affine,
+}
+
+impl PublicKey {
+    fn read<R: Read>(r: &mut R) -> Result<Self> {
+        let mut bytes = vec![0u8; PUBLIC_KEY_SIZE];
+        r.read_exact(&mut bytes)?;
+        let (g1, g2) = bytes.split_at(6 * G1_UNCOMPRESSED_SIZE);
+        let g1 = decode_points::<G1Affine>(g1, Compress::No, "public key G1")?;
+        let g2 = decode_points::<G2Affine>(g2, Compress::No, "public key G2")?;
+        Ok(Self {
+            tau_g1: (g1[0], g1[1]),
+            alpha_g1: (g1[2], g1[3]),
+            beta_g1: (g1[4], g1[5]),
+            tau_g2: g2[0],
+            alpha_g2: g2[1],
+            beta_g2: g2[2],
+        })
+    }
+
+    fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        let g1 = [self.tau_g1, self.alpha_g1, self.beta_g1];
+        for p in g1.iter().flat_map(|(s, s_x)| [s, s_x]) {
+            p.serialize_uncompressed(&mut *w)?;
+        }
+        for p in [self.tau_g2, self.alpha_g2, self.beta_g2] {
+            p.serialize_uncompressed(&mut *w)?;
+        }
+        Ok(())
+    }
+}
+
+/// A `challenge` or `response` file of the ceremony.
+///
+/// Power sections are possibly truncated to a prefix, see
+/// [`Transcript::read()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    /// kind of the file, which fixes its point encoding
+    pub kind: TranscriptKind,
+    /// number of powers of tau in G2 of the ceremony
+    pub tau_powers_length: usize,
+    /// BLAKE2b hash of the file this one was computed from
+    pub previous_hash: [u8; HASH_SIZE],
+    /// tau^i.[1], for i < 2 * `tau_powers_length` - 1
+    pub tau_g1: Vec<G1Affine>,
+    /// tau^i.[1]_2, for i < `tau_powers_length`
+    pub tau_g2: Vec<G2Affine>,
+    /// alpha.tau^i.[1], for i < `tau_powers_length`
+    pub alpha_tau_g1: Vec<G1Affine>,
+    /// beta.tau^i.[1], for i < `tau_powers_length`
+    pub beta_tau_g1: Vec<G1Affine>,
+    /// beta.[1]_2
+    pub beta_g2: G2Affine,
+    /// public key of the contribution, only in `response` files
+    pub public_key: Option<PublicKey>,
+}
+
+impl Transcript {
+    /// Parse a file of `kind`, reading at most `max_points` from each of the
+    /// power sections (all of them if `None`), as these files are several GB.
+    ///
+    /// Every point read is checked to be in the prime order subgroup and not
+    /// the point at infinity, as `powersoftau` does.
+    pub fn read<R: Read + Seek>(
+        r: &mut R,
+        kind: TranscriptKind,
+        max_points: Option<usize>,
+    ) -> Result<Self> {
+        let file_size = r.seek(SeekFrom::End(0))?;
+        let n = kind.tau_powers_length(file_size)?;
+        let (g1, g2) = kind.point_sizes();
+        let compress = kind.compress();
+        let cap = |len: usize| max_points.map_or(len, |m| m.min(len));
+
+        r.seek(SeekFrom::Start(0))?;
+        let mut previous_hash = [0u8; HASH_SIZE];
+        r.read_exact(&mut previous_hash)?;
+
+        // sections follow each other, in this order
+        let tau_g1_pos = HASH_SIZE as u64;
+        let tau_g2_pos = tau_g1_pos + (2 * n as u64 - 1) * g1;
+        let alpha_pos = tau_g2_pos + n as u64 * g2;
+        let beta_pos = alpha_pos + n as u64 * g1;
+        let beta_g2_pos = beta_pos + n as u64 * g1;
+        let public_key_pos = beta_g2_pos + g2;
+
+        let tau_g1 = read_points(r, tau_g1_pos, cap(2 * n - 1), compress, "tauG1")?;
+        let tau_g2 = read_points(r, tau_g2_pos, cap(n), compress, "tauG2")?;
+        let alpha_tau_g1 = read_points(r, alpha_pos, cap(n), compress, "alphaTauG1")?;
+        let beta_tau_g1 = read_points(r, beta_pos, cap(n), compress, "betaTauG1")?;
+        let beta_g2 = read_points(r, beta_g2_pos, 1, compress, "betaG2")?[0];
+        let public_key = match kind {
+            TranscriptKind::Challenge => None,
+            TranscriptKind::Response => {
+                r.seek(SeekFrom::Start(public_key_pos))?;
+                Some(PublicKey::read(r)?)
+            },
+        };
+
+        Ok(Self {
+            kind,
+            tau_powers_length: n,
+            previous_hash,
+            tau_g1,
+            tau_g2,
+            alpha_tau_g1,
+            beta_tau_g1,
+            beta_g2,
+            public_key,
+        })
+    }
+
+    /// Serialize into the format of `self.kind`.
+    ///
+    /// # Note
+    /// Fails if the power sections are truncated (e.g. read with
+    /// `max_points`), or if a `response` has no public key, as the file would
+    /// be malformed.
+    pub fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        let n = self.tau_powers_length;
+        if self.tau_g1.len() != 2 * n - 1
+            || self.tau_g2.len() != n
+            || self.alpha_tau_g1.len() != n
+            || self.beta_tau_g1.len() != n
+        {
+            return Err(Error::InvalidSrs(format!(
+                "Incomplete powers of tau for {n} powers in G2"
+            )));
+        }
+        if (self.kind == TranscriptKind::Response) != self.public_key.is_some() {
+            return Err(Error::InvalidSrs(format!(
+                "Only response files have a public key, got a {} file",
+                self.kind.basename()
+            )));
+        }
+
+        let compress = self.kind.compress();
+        w.write_all(&self.previous_hash)?;
+        for p in &self.tau_g1 {
+            p.serialize_with_mode(&mut *w, compress)?;
+        }
+        for p in &self.tau_g2 {
+            p.serialize_with_mode(&mut *w, compress)?;
+        }
+        for p in self.alpha_tau_g1.iter().chain(&self.beta_tau_g1) {
+            p.serialize_with_mode(&mut *w, compress)?;
+        }
+        self.beta_g2.serialize_with_mode(&mut *w, compress)?;
+        if let Some(public_key) = &self.public_key {
+            public_key.write(w)?;
+        }
+        Ok(())
+    }
+
+    /// Convert into KZG10 public parameters supporting polynomials up to
+    /// `supported_degree`.
+    pub fn into_universal_params(
+        self,
+        supported_degree: usize,
+    ) -> Result<UniversalParams<Bls12_381>> {
+        if !(1..self.tau_g1.len()).contains(&supported_degree) || self.tau_g2.len() < 2 {
+            return Err(Error::DegreeOutOfRange {
+                degree: supported_degree,
+                max: self.tau_g1.len().saturating_sub(1),
+            });
+        }
+        let mut powers_of_g = self.tau_g1;
+        powers_of_g.truncate(supported_degree + 1);
+
+        let h = self.tau_g2[0];
+        let beta_h = self.tau_g2[1];
+        Ok(UniversalParams {
+            powers_of_g,
+            // NOTE: used for hiding variant of KZG, not supported in the ceremony.
+            powers_of_gamma_g: BTreeMap::new(),
+            h,
+            beta_h,
+            // NOTE: not supported in the ceremony.
+            neg_powers_of_h: BTreeMap::new(),
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+        })
+    }
+}
+
+fn read_points<P: AffineRepr, R: Read + Seek>(
+    r: &mut R,
+    pos: u64,
+    n: usize,
+    compress: Compress,
+    section: &str,
+) -> Result<Vec<P>> {
+    let mut bytes = vec![0u8; n * P::generator().serialized_size(compress)];
+    r.seek(SeekFrom::Start(pos))?;
+    r.read_exact(&mut bytes)?;
+    decode_points(&bytes, compress, section)
+}
+
+// Decode consecutive points in parallel with the `parallel` feature,
+// rejecting the point at infinity as `powersoftau` does.
+fn decode_points<P: AffineRepr>(bytes: &[u8], compress: Compress, section: &str) -> Result<Vec<P>> {
+    cfg_chunks!(bytes, P::generator().serialized_size(compress))
+        .enumerate()
+        .map(|(i, b)| {
+            let point = P::deserialize_with_mode(b, compress, Validate::Yes)
+                .map_err(|e| Error::InvalidPoint(format!("{section} point {i}: {e}")))?;
+            if point.is_zero() {
+                return Err(Error::InvalidPoint(format!(
+                    "{section} point {i} is the point at infinity"
+                )));
+            }
+            Ok(point)
+        })
+        .collect()
+}
+
+/// BLAKE2b hash of a whole `challenge` or `response` file, which the next
+/// file of the ceremony starts with. The hash of a `response` is the one
+/// published by its contributor.
+pub fn transcript_hash(path: impl AsRef<Path>) -> Result<[u8; HASH_SIZE]> {
+    let path = path.as_ref();
+    let f = File::open(path).map_err(|e| Error::file(path, e))?;
+    let mut hasher = Blake2b512::new();
+    io::copy(&mut BufReader::new(f), &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
+/// Check that each of `files`, successive `challenge` and `response` files of
+/// the ceremony, starts with the hash of the one before it (see
+/// [`transcript_hash()`]), and returns the hash of the last one.
+///
+/// Only the chain of hashes is checked, not the contributions themselves.
+pub fn verify_chain<P: AsRef<Path>>(files: &[P]) -> Result<[u8; HASH_SIZE]> {
+    let (first, rest) = files
+        .split_first()
+        .ok_or_else(|| Error::MalformedTranscript("No transcript to verify".into()))?;
+    let mut hash = transcript_hash(first)?;
+    for (prev, path) in files.iter().zip(rest) {
+        let path = path.as_ref();
+        let mut previous_hash = [0u8; HASH_SIZE];
+        File::open(path)
+            .and_then(|mut f| f.read_exact(&mut previous_hash))
+            .map_err(|e| Error::file(path, e))?;
+        if previous_hash != hash {
+            return Err(Error::MalformedTranscript(format!(
+                "{} does not start with the hash of {}",
+                path.display(),
+                prev.as_ref().display()
+            )));
+        }
+        hash = transcript_hash(path)?;
+    }
+    Ok(hash)
+}
+
+/// Zcash's Powers of Tau ceremony for Sapling, as a [`Ceremony`]: its artifacts
+/// are pinned by [`ZCASH_POWERSOFTAU_CHECKSUMS`], and it's parsed from the
+/// transcript in [`SrsConfig::transcript_dir()`], whose hash must be one of
+/// [`ZCASH_POWERSOFTAU_TRANSCRIPT_HASHES`]. Other transcripts are rejected
+/// unless opted in with [`TrustPolicy::Custom`], in which case all the points
+/// parsed are checked instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZcashPowersOfTau;
+
+impl Ceremony for ZcashPowersOfTau {
+    type Pairing = Bls12_381;
+
+    const NAME: &'static str = ZCASH_POWERSOFTAU;
+    const CURVE: CurveId = CurveId::Bls12_381;
+    const MAX_DEGREE: usize = MAX_DEGREE;
+
+    fn artifacts() -> Vec<Artifact> {
+        ZCASH_POWERSOFTAU_CHECKSUMS
+            .iter()
+            .map(|(degree, sha256)| Artifact {
+                ceremony: Self::NAME.to_string(),
+                degree: *degree,
+                name: Self::artifact_name(*degree, PointEncoding::Uncompressed),
+                sha256: *sha256,
+                encoding: PointEncoding::Uncompressed,
+                chunk_root: None,
+            })
+            .collect()
+    }
+
+    fn setup_from_raw(
+        supported_degree: usize,
+        cfg: &SrsConfig,
+    ) -> Result<UniversalParams<Bls12_381>> {
+        let (kind, path) = last_transcript(cfg.transcript_root::<Self>())?;
+        if ZCASH_POWERSOFTAU_TRANSCRIPT_HASHES.contains(&transcript_hash(&path)?) {
+            return read_transcript(supported_degree, kind, &path);
+        }
+        match cfg.trust {
+            TrustPolicy::Pinned => Err(Error::UntrustedFile { path }),
+            TrustPolicy::Custom => {
+                let pp = read_transcript(supported_degree, kind, &path)?;
+                verify_powers(&pp)?;
+                Ok(pp)
+            },
+        }
+    }
+}
+
+/// Retreive public parameters when given as input the maximum degree degree for
+/// the polynomial commitment scheme.
+/// This API is similar to [KZG10::setup][setup]
+///
+/// # Note
+/// Parameter files are served from the cache and pinned by checksum as for
+/// [`aztec20::setup()`](crate::kzg10::aztec20::setup). No artifact of this
+/// ceremony is released yet: use a release listing some in its manifest (see
+/// [`SrsConfig::release_version()`]), or fill the cache from the original
+/// transcript with [`cache_from_transcript()`].
+///
+/// [setup]: https://docs.rs/ark-poly-commit/0.4.0/ark_poly_commit/kzg10/struct.KZG10.html#method.setup
+pub fn setup(supported_degree: usize) -> Result<UniversalParams<Bls12_381>> {
+    setup_with_config(supported_degree, &SrsConfig::default())
+}
+
+/// Same as [`setup()`], but the cache location, download source and checks
+/// are taken from `cfg` instead of the defaults.
+pub fn setup_with_config(
+    supported_degree: usize,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<Bls12_381>> {
+    ceremony::setup_with_config::<ZcashPowersOfTau>(supported_degree, cfg)
+}
+
+/// Fill the default cache with the parameter files used by [`setup()`] for
+/// each of `degrees`, see [`ceremony::prefetch_with_config()`].
+pub fn prefetch(degrees: &[usize]) -> Result<Vec<PathBuf>> {
+    prefetch_with_config(degrees, &SrsConfig::default())
+}
+
+/// Same as [`prefetch()`], filling the cache configured in `cfg`.
+pub fn prefetch_with_config(degrees: &[usize], cfg: &SrsConfig) -> Result<Vec<PathBuf>> {
+    ceremony::prefetch_with_config::<ZcashPowersOfTau>(degrees, cfg)
+}
+
+/// Run setup by parsing directly from the last file of the ceremony under
+/// `./data/zcash_powersoftau`, pinned by [`ZCASH_POWERSOFTAU_TRANSCRIPT_HASHES`],
+/// see [`ZcashPowersOfTau`].
+pub fn setup_from_raw(supported_degree: usize) -> Result<UniversalParams<Bls12_381>> {
+    setup_from_raw_with_config(supported_degree, &SrsConfig::default())
+}
+
+/// Same as [`setup_from_raw()`], reading the transcript from the transcript
+/// directory of `cfg`.
+pub fn setup_from_raw_with_config(
+    supported_degree: usize,
+    cfg: &SrsConfig,
+) -> Result<UniversalParams<Bls12_381>> {
+    ceremony::setup_from_raw_with_config::<ZcashPowersOfTau>(supported_degree, cfg)
+}
+
+/// Parse parameters supporting `supported_degree` from the transcript
+/// directory of `cfg` and store them in its cache, so that [`setup()`] serves
+/// them from then on, see [`ceremony::cache_from_raw_with_config()`]. The
+/// header of the cached file records the hash of the transcript, see
+/// [`transcript_hash()`].
+///
+/// Returns the path of the cached file.
+pub fn cache_from_transcript(supported_degree: usize, cfg: &SrsConfig) -> Result<PathBuf> {
+    let (_, transcript) = last_transcript(cfg.transcript_root::<ZcashPowersOfTau>())?;
+    ceremony::cache_from_raw_with_config::<ZcashPowersOfTau, _>(
+        supported_degree,
+        cfg,
+        [transcript_hash(&transcript)?],
+    )
+}
+
+/// Run setup by parsing the `response` file under `dir`, or its `challenge`
+/// file if there is no `response`, of a ceremony of any size. Only the points
+/// needed for `supported_degree` are read.
+///
+/// The file must hash to `expected_hash`, the result of [`verify_chain()`]
+/// over the files leading to it, or the hash published by the coordinator.
+pub fn setup_from_transcript(
+    supported_degree: usize,
+    dir: impl AsRef<Path>,
+    expected_hash: &[u8; HASH_SIZE],
+) -> Result<UniversalParams<Bls12_381>> {
+    let (kind, path) = last_transcript(dir.as_ref())?;
+    if transcript_hash(&path)? != *expected_hash {
+        return Err(Error::ChecksumMismatch { path });
+    }
+    read_transcript(supported_degree, kind, &path)
+}
+
+// Parse the transcript at `path`, whose hash is already checked
+fn read_transcript(
+    supported_degree: usize,
+    kind: TranscriptKind,
+    path: &Path,
+) -> Result<UniversalParams<Bls12_381>> {
+    let mut f = BufReader::new(File::open(path).map_err(|e| Error::file(path, e))?);
+    Transcript::read(&mut f, kind, Some(supported_degree + 1))
+        .map_err(|e| e.context(path.display()))?
+        .into_universal_params(supported_degree)
+}
+
+// The `response` file under `dir`, or its `challenge` file
+fn last_transcript(dir: impl AsRef<Path>) -> Result<(TranscriptKind, PathBuf)> {
+    [TranscriptKind::Response, TranscriptKind::Challenge]
+        .into_iter()
+        .map(|kind| (kind, dir.as_ref().join(kind.basename())))
+        .find(|(_, path)| path.exists())
+        .ok_or_else(|| {
+            Error::MalformedTranscript(format!(
+                "No response or challenge file in {}",
+                dir.as_ref().display()
+            ))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{load::SrsHeader, VerificationLevel};
+    use ark_bls12_381::Fr;
+    use ark_ec::{pairing::Pairing, CurveGroup};
+    use ark_ff::{Field, UniformRand};
+    use ark_std::io::Cursor;
+
+    // the accumulator of `n` powers of a known toxic waste, as a file of `kind`
+    fn mock_transcript(
+        kind: TranscriptKind,
+        n: usize,
+        previous_hash: [u8; HASH_SIZE],
+    ) -> (Transcript, Fr) {
+        let rng = &mut ark_std::test_rng();
+        let [tau, alpha, beta, s] = [(); 4].map(|_| Fr::rand(rng));
+        let g = G1Affine::generator();
+        let h = G2Affine::generator();
+        let powers: Vec<Fr> = (0..2 * n - 1).map(|i| tau.pow([i as u64])).collect();
+        let g1 = |x: &Fr| (g * x).into_affine();
+        let g2 = |x: &Fr| (h * x).into_affine();
+        let public_key = PublicKey {
+            tau_g1: (g1(&s), g1(&(s * tau))),
+            alpha_g1: (g1(&s), g1(&(s * alpha))),
+            beta_g1: (g1(&s), g1(&(s * beta))),
+            tau_g2: g2(&tau),
+            alpha_g2: g2(&alpha),
+            beta_g2: g2(&beta),
+        };
+        let transcript = Transcript {
+            kind,
+            tau_powers_length: n,
+            previous_hash,
+            tau_g1: powers.iter().map(g1).collect(),
+            tau_g2: powers[..n].iter().map(g2).collect(),
+            alpha_tau_g1: powers[..n].iter().map(|t| g1(&(alpha * t))).collect(),
+            beta_tau_g1: powers[..n].iter().map(|t| g1(&(beta * t))).collect(),
+            beta_g2: g2(&beta),
+            public_key: (kind == TranscriptKind::Response).then_some(public_key),
+        };
+        (transcript, tau)
+    }
+
+    fn to_bytes(transcript: &Transcript) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        transcript.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_transcript_parsing() -> Result<()> {
+        let blank = Blake2b512::digest([]).into();
+        for kind in [TranscriptKind::Challenge, TranscriptKind::Response] {
+            let (transcript, tau) = mock_transcript(kind, 8, blank);
+            let bytes = to_bytes(&transcript);
+            assert_eq!(bytes.len() as u64, kind.file_size(8));
+            assert_eq!(kind.tau_powers_length(bytes.len() as u64)?, 8);
+
+            // serialization is the exact inverse
+            let parsed = Transcript::read(&mut Cursor::new(&bytes), kind, None)?;
+            assert_eq!(parsed, transcript);
+            assert_eq!(
+                Bls12_381::pairing(parsed.alpha_tau_g1[1], parsed.tau_g2[0]),
+                Bls12_381::pairing(parsed.alpha_tau_g1[0], parsed.tau_g2[1]),
+            );
+
+            let pp = Transcript::read(&mut Cursor::new(&bytes), kind, Some(4))?
+                .into_universal_params(3)?;
+            assert_eq!(pp.powers_of_g.len(), 4);
+            assert_eq!(
+                pp.powers_of_g[3],
+                (G1Affine::generator() * tau.pow([3])).into_affine()
+            );
+            assert_eq!(pp.beta_h, transcript.tau_g2[1]);
+            crate::verify::kzg10::verify_powers(&pp)?;
+
+            // corrupted files are rejected
+            let mut bad = bytes.clone();
+            bad[HASH_SIZE + 10] ^= 1;
+            assert!(Transcript::read(&mut Cursor::new(&bad), kind, None).is_err());
+            let short = &bytes[..bytes.len() - 1];
+            assert!(Transcript::read(&mut Cursor::new(short), kind, None).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_zcash_powersoftau() -> Result<()> {
+        // challenge -> response -> next challenge of a single contribution
+        let dir = tempfile::tempdir()?;
+        let files = [
+            TranscriptKind::Challenge,
+            TranscriptKind::Response,
+            TranscriptKind::Challenge,
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, kind)| dir.path().join(format!("{i}-{}", kind.basename())))
+        .collect::<Vec<_>>();
+        let mut previous_hash = Blake2b512::digest([]).into();
+        for (path, kind) in files.iter().zip([
+            TranscriptKind::Challenge,
+            TranscriptKind::Response,
+            TranscriptKind::Challenge,
+        ]) {
+            std::fs::write(path, to_bytes(&mock_transcript(kind, 8, previous_hash).0))?;
+            previous_hash = transcript_hash(path)?;
+        }
+        assert_eq!(verify_chain(&files)?, previous_hash);
+        assert!(verify_chain(&[&files[0], &files[2]]).is_err());
+        let mut tampered = std::fs::read(&files[1])?;
+        tampered[HASH_SIZE] ^= 1;
+        std::fs::write(&files[1], tampered)?;
+        assert!(verify_chain(&files).is_err());
+
+        // the last file is parsed from the transcript directory, if it hashes
+        // to the end of the chain
+        std::fs::rename(&files[2], dir.path().join("challenge"))?;
+        let pp = setup_from_transcript(10, dir.path(), &previous_hash)?;
+        assert_eq!(pp.powers_of_g.len(), 11);
+        assert!(matches!(
+            setup_from_transcript(10, dir.path(), &transcript_hash(&files[1])?),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+
+        // not pinned, so only parsed when opted in, checking all points
+        let cfg = SrsConfig::default()
+            .transcript_dir(dir.path())
+            .verification(VerificationLevel::Full);
+        assert!(matches!(
+            setup_from_raw_with_config(10, &cfg),
+            Err(Error::UntrustedFile { .. })
+        ));
+        let cfg = cfg.trust(TrustPolicy::Custom);
+        assert_eq!(setup_from_raw_with_config(10, &cfg)?, pp);
+        assert!(setup_from_raw_with_config(15, &cfg).is_err());
+
+        // then served from the cache as any other ceremony
+        let cache = tempfile::tempdir()?;
+        let cfg = cfg.cache_dir(cache.path()).offline(true);
+        assert!(matches!(
+            setup_with_config(8, &cfg),
+            Err(Error::UnsupportedDegree { .. })
+        ));
+        let path = cache_from_transcript(10, &cfg)?;
+        let header = SrsHeader::read(&path)?.unwrap();
+        assert_eq!(header.ceremony, ZcashPowersOfTau::NAME);
+        assert_eq!(header.source_hashes, [hex::encode(previous_hash)]);
+        assert_eq!(setup_with_config(8, &cfg)?.powers_of_g, pp.powers_of_g[..9]);
+        assert!(setup(MAX_DEGREE + 1).is_err());
+        Ok(())
+    }
+}
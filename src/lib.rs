@@ -3,9 +3,20 @@
 #![deny(missing_docs)]
 #![no_std]
 
+pub mod cache;
+pub mod ceremony;
+pub mod config;
 pub mod constants;
+pub mod download;
+pub mod errors;
 pub mod kzg10;
 pub mod load;
+pub mod lock;
+pub mod manifest;
+pub mod verify;
+
+pub use config::{Decompression, SrsConfig, TrustPolicy, VerificationLevel};
+pub use errors::{Error, Result};
 
 extern crate alloc;
 extern crate std;
@@ -1,26 +1,45 @@
 //! Utils for persisting serialized data to files and loading them into memroy.
 //! We deal with `ark-serialize::CanonicalSerialize` compatible objects.
 
+use crate::{
+    config::{SrsConfig, TrustPolicy},
+    download::Downloader,
+    errors::{Error, Result},
+    lock::CacheLock,
+};
 use alloc::{
     borrow::ToOwned,
     format,
     string::{String, ToString},
+    vec,
     vec::Vec,
 };
-use anyhow::{anyhow, Context, Result};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, Write};
-use ark_std::rand::{distributions::Alphanumeric, Rng as _};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_poly_commit::kzg10::UniversalParams;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Read, Validate, Write};
+use ark_std::{
+    any::TypeId,
+    cfg_chunks,
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+};
 use directories::ProjectDirs;
+use memmap2::Mmap;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::{
-    fs::{self, create_dir_all, File},
-    io::BufReader,
+    fs::{self, File},
+    io::{BufReader, Seek, SeekFrom},
     path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 /// store any serializable data into `dest`.
 pub fn store_data<T: CanonicalSerialize>(data: T, dest: PathBuf) -> Result<()> {
-    let mut f = File::create(dest)?;
+    let mut f = File::create(&dest).map_err(|e| Error::file(dest, e))?;
     let mut bytes = Vec::new();
     data.serialize_uncompressed(&mut bytes)?;
     Ok(f.write_all(&bytes)?)
@@ -28,7 +47,7 @@ pub fn store_data<T: CanonicalSerialize>(data: T, dest: PathBuf) -> Result<()> {
 
 /// load any deserializable data into memory
 pub fn load_data<T: CanonicalDeserialize>(src: PathBuf) -> Result<T> {
-    let f = File::open(src)?;
+    let f = File::open(&src).map_err(|e| Error::file(src, e))?;
     // maximum 8 KB of buffer for memory exhaustion protection for malicious file
     let mut reader = BufReader::with_capacity(8000, f);
     let mut bytes = Vec::new();
@@ -37,52 +56,844 @@ pub fn load_data<T: CanonicalDeserialize>(src: PathBuf) -> Result<T> {
     Ok(T::deserialize_uncompressed_unchecked(&bytes[..])?)
 }
 
+/// Magic bytes at the start of self-describing SRS files, see [`SrsHeader`]
+pub const SRS_MAGIC: &[u8; 8] = b"ARKSRS\0\0";
+/// Current version of the self-describing SRS file format
+pub const SRS_FORMAT_VERSION: u32 = 1;
+// magic, format version and header length
+const SRS_PREAMBLE_SIZE: usize = 16;
+
+/// Curve of the points of an SRS file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurveId {
+    /// BN254, see [`ark_bn254`]
+    Bn254,
+    /// BLS12-381, see [`ark_bls12_381`]
+    Bls12_381,
+    /// BLS12-377, see [`ark_bls12_377`]
+    Bls12_377,
+}
+
+impl CurveId {
+    /// The id of the curve of `E`, if known
+    pub fn of<E: Pairing>() -> Option<Self> {
+        let id = TypeId::of::<E>();
+        if id == TypeId::of::<ark_bn254::Bn254>() {
+            Some(Self::Bn254)
+        } else if id == TypeId::of::<ark_bls12_381::Bls12_381>() {
+            Some(Self::Bls12_381)
+        } else if id == TypeId::of::<ark_bls12_377::Bls12_377>() {
+            Some(Self::Bls12_377)
+        } else {
+            None
+        }
+    }
+}
+
+/// Encoding of the points of an SRS file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PointEncoding {
+    /// arkworks compressed encoding, half the size but slower to decode
+    Compressed,
+    /// arkworks uncompressed encoding
+    #[default]
+    Uncompressed,
+}
+
+impl From<PointEncoding> for Compress {
+    fn from(encoding: PointEncoding) -> Self {
+        match encoding {
+            PointEncoding::Compressed => Compress::Yes,
+            PointEncoding::Uncompressed => Compress::No,
+        }
+    }
+}
+
+/// Metadata of a self-describing SRS file, laid out as:
+///
+/// - [`SRS_MAGIC`]
+/// - the format version, [`SRS_FORMAT_VERSION`] as `u32` little-endian
+/// - the length of the header as `u32` little-endian
+/// - the header, as json
+/// - the payload: the `CanonicalSerialize` encoding of the KZG10
+///   `UniversalParams` with `encoding`
+///
+/// Files written by [`store_data()`] (e.g. the released ones) are the bare
+/// payload, in the uncompressed encoding. They are still read as *legacy*
+/// files, without a header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SrsHeader {
+    /// curve of the points
+    pub curve: CurveId,
+    /// commitment scheme the parameters are meant for, e.g. `kzg10`
+    pub scheme: String,
+    /// ceremony the parameters are taken from, e.g. `aztec20`
+    pub ceremony: String,
+    /// max degree supported, i.e. number of G1 powers minus one
+    pub degree: usize,
+    /// number of G2 powers
+    pub num_g2: usize,
+    /// encoding of the points in the payload
+    pub encoding: PointEncoding,
+    /// sha256sum of the payload
+    #[serde(with = "hex::serde")]
+    pub content_hash: [u8; 32],
+    /// hex-encoded hashes of the files the parameters are derived from, e.g.
+    /// the BLAKE2b checksums of the ceremony transcripts
+    pub source_hashes: Vec<String>,
+}
+
+impl SrsHeader {
+    /// Header of KZG10 parameters from `ceremony` over `curve`, its degree,
+    /// number of G2 powers and content hash are filled by [`store_srs()`].
+    pub fn kzg10(curve: CurveId, ceremony: &str, encoding: PointEncoding) -> Self {
+        Self {
+            curve,
+            scheme: "kzg10".to_string(),
+            ceremony: ceremony.to_string(),
+            degree: 0,
+            num_g2: 0,
+            encoding,
+            content_hash: [0u8; 32],
+            source_hashes: Vec::new(),
+        }
+    }
+
+    /// Set the hashes of the files the parameters are derived from
+    pub fn source_hashes<T: AsRef<[u8]>>(mut self, hashes: impl IntoIterator<Item = T>) -> Self {
+        self.source_hashes = hashes.into_iter().map(hex::encode).collect();
+        self
+    }
+
+    /// The header and the payload of a self-describing SRS file, or `None`
+    /// and the whole of `bytes` for a legacy file. The content hash is not
+    /// checked.
+    pub fn split(bytes: &[u8]) -> Result<(Option<Self>, &[u8])> {
+        let Some(header_len) = Self::header_len(bytes)? else {
+            return Ok((None, bytes));
+        };
+        let (header, payload) = bytes[SRS_PREAMBLE_SIZE..]
+            .split_at_checked(header_len)
+            .ok_or_else(|| Error::MalformedCache("SRS file too short for its header".into()))?;
+        Ok((Some(Self::from_json(header)?), payload))
+    }
+
+    /// Read the header of the SRS file at `src`, `None` for a legacy file
+    pub fn read(src: &Path) -> Result<Option<Self>> {
+        let mut f = File::open(src).map_err(|e| Error::file(src, e))?;
+        let (header, _) = Self::read_layout(|range| {
+            let mut buf = vec![0u8; (range.end - range.start) as usize];
+            f.seek(SeekFrom::Start(range.start))?;
+            // files shorter than a preamble are legacy (or malformed)
+            match f.read_exact(&mut buf) {
+                Ok(()) => Ok(buf),
+                Err(e) if range.start == 0 && e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    Ok(Vec::new())
+                },
+                Err(e) => Err(e.into()),
+            }
+        })
+        .map_err(|e| e.context(src.display()))?;
+        Ok(header)
+    }
+
+    // The header and the payload offset of a file, reading its byte ranges
+    // with `read_range`
+    pub(crate) fn read_layout(
+        mut read_range: impl FnMut(Range<u64>) -> Result<Vec<u8>>,
+    ) -> Result<(Option<Self>, u64)> {
+        let preamble = read_range(0..SRS_PREAMBLE_SIZE as u64)?;
+        let Some(header_len) = Self::header_len(&preamble)? else {
+            return Ok((None, 0));
+        };
+        let end = (SRS_PREAMBLE_SIZE + header_len) as u64;
+        let header = Self::from_json(&read_range(SRS_PREAMBLE_SIZE as u64..end)?)?;
+        Ok((Some(header), end))
+    }
+
+    // The header length if `bytes` starts a self-describing file, checking
+    // its format version
+    fn header_len(bytes: &[u8]) -> Result<Option<usize>> {
+        if !bytes.starts_with(SRS_MAGIC) {
+            return Ok(None);
+        }
+        let preamble = bytes
+            .get(..SRS_PREAMBLE_SIZE)
+            .ok_or_else(|| Error::MalformedCache("SRS file too short for a header".into()))?;
+        let field = |i: usize| {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(&preamble[8 + i * 4..12 + i * 4]);
+            u32::from_le_bytes(buf)
+        };
+        let version = field(0);
+        if version != SRS_FORMAT_VERSION {
+            return Err(Error::MalformedCache(format!(
+                "Unsupported SRS format version {version}, expect {SRS_FORMAT_VERSION}"
+            )));
+        }
+        Ok(Some(field(1) as usize))
+    }
+
+    fn from_json(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes)
+            .map_err(|e| Error::MalformedCache(format!("Invalid SRS header: {e}")))
+    }
+
+    fn to_json(&self) -> Vec<u8> {
+        // serializing plain strings and integers never fails
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+}
+
+/// Encode `pp` as a self-describing SRS file, filling the degree, number of
+/// G2 powers and content hash of `header`.
+pub fn encode_srs<E: Pairing>(pp: &UniversalParams<E>, mut header: SrsHeader) -> Result<Vec<u8>> {
+    let mut payload = Vec::new();
+    pp.serialize_with_mode(&mut payload, header.encoding.into())?;
+    header.degree = pp.powers_of_g.len().saturating_sub(1);
+    header.num_g2 = 2 + pp.neg_powers_of_h.len();
+    header.content_hash = Sha256::digest(&payload).into();
+
+    let json = header.to_json();
+    let mut bytes = Vec::with_capacity(SRS_PREAMBLE_SIZE + json.len() + payload.len());
+    bytes.extend_from_slice(SRS_MAGIC);
+    bytes.extend_from_slice(&SRS_FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&json);
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+/// Store `pp` into `dest` as a self-describing SRS file, see [`SrsHeader`]
+pub fn store_srs<E: Pairing>(
+    pp: &UniversalParams<E>,
+    header: SrsHeader,
+    dest: &Path,
+) -> Result<()> {
+    let bytes = encode_srs(pp, header)?;
+    fs::write(dest, bytes).map_err(|e| Error::file(dest, e))
+}
+
+/// Decode a self-describing SRS file, checking its curve and content hash, or
+/// a legacy file written by [`store_data()`] (returning no header). Points are
+/// validated if `validate`.
+pub fn decode_srs<E: Pairing>(
+    bytes: &[u8],
+    validate: bool,
+) -> Result<(Option<SrsHeader>, UniversalParams<E>)> {
+    let validate = if validate {
+        Validate::Yes
+    } else {
+        Validate::No
+    };
+    let (header, payload) = SrsHeader::split(bytes)?;
+    let Some(header) = header else {
+        return Ok((None, decode_payload(bytes, Compress::No, validate)?));
+    };
+    if CurveId::of::<E>() != Some(header.curve) {
+        return Err(Error::MalformedCache(format!(
+            "SRS file over {:?}, not the requested curve",
+            header.curve
+        )));
+    }
+    if Sha256::digest(payload)[..] != header.content_hash {
+        return Err(Error::MalformedCache(
+            "SRS payload doesn't match its content hash".into(),
+        ));
+    }
+    let pp = decode_payload::<E>(payload, header.encoding.into(), validate)?;
+    if pp.powers_of_g.len() != header.degree + 1 {
+        return Err(Error::MalformedCache(format!(
+            "SRS header expects degree {}, got {}",
+            header.degree,
+            pp.powers_of_g.len().saturating_sub(1)
+        )));
+    }
+    Ok((Some(header), pp))
+}
+
+// Decode KZG10 public parameters as serialized by `CanonicalSerialize`, the
+// points of `powers_of_g` are decoded in parallel under the `parallel` feature
+fn decode_payload<E: Pairing>(
+    payload: &[u8],
+    compress: Compress,
+    validate: Validate,
+) -> Result<UniversalParams<E>> {
+    let (powers_of_g, mut reader) = decode_g1_vec::<E::G1Affine>(payload, compress, validate)?;
+    let powers_of_gamma_g = BTreeMap::deserialize_with_mode(&mut reader, compress, validate)?;
+    let h = E::G2Affine::deserialize_with_mode(&mut reader, compress, validate)?;
+    let beta_h = E::G2Affine::deserialize_with_mode(&mut reader, compress, validate)?;
+    let neg_powers_of_h = BTreeMap::deserialize_with_mode(&mut reader, compress, validate)?;
+    Ok(UniversalParams {
+        powers_of_g,
+        powers_of_gamma_g,
+        h,
+        beta_h,
+        neg_powers_of_h,
+        prepared_h: h.into(),
+        prepared_beta_h: beta_h.into(),
+    })
+}
+
+// Decode the `u64`-prefixed vector of points at the start of `bytes`, and
+// return the rest of `bytes`
+fn decode_g1_vec<A: AffineRepr>(
+    bytes: &[u8],
+    compress: Compress,
+    validate: Validate,
+) -> Result<(Vec<A>, &[u8])> {
+    let point_size = A::generator().serialized_size(compress);
+    let len = u64::deserialize_uncompressed(bytes)? as usize;
+    let (points, rest) = len
+        .checked_mul(point_size)
+        .and_then(|size| bytes[8..].split_at_checked(size))
+        .ok_or_else(|| Error::MalformedCache(format!("Too short for {len} points")))?;
+    let points = cfg_chunks!(points, point_size)
+        .map(|b| A::deserialize_with_mode(b, compress, validate))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((points, rest))
+}
+
+/// Load a self-describing or legacy SRS file, see [`decode_srs()`]
+pub fn load_srs<E: Pairing>(
+    src: &Path,
+    validate: bool,
+) -> Result<(Option<SrsHeader>, UniversalParams<E>)> {
+    let bytes = fs::read(src).map_err(|e| Error::file(src, e))?;
+    decode_srs(&bytes, validate).map_err(|e| e.context(src.display()))
+}
+
+/// Memory-mapped KZG10 parameter file, as written by [`store_data()`], or by
+/// [`store_srs()`] with uncompressed points.
+///
+/// The layout of the (payload of the) file is fixed for uncompressed arkworks
+/// affine points: `powers_of_g` (a `u64` length followed by the points), an
+/// empty `powers_of_gamma_g`, `h`, `beta_h`, and an empty `neg_powers_of_h`.
+/// Header and checksum are checked once on opening, after which points are
+/// decoded on demand straight from the mapped pages. The file itself stays
+/// mapped, its pages being shared by all processes mapping the same file: only
+/// the points decoded (e.g. all of them for [`MappedSrs::universal_params()`])
+/// are copied into memory.
+///
+/// # Note
+/// The file must not be modified while mapped.
+pub struct MappedSrs<E: Pairing> {
+    mmap: Mmap,
+    // start of the payload
+    offset: usize,
+    num_powers: usize,
+    h: E::G2Affine,
+    beta_h: E::G2Affine,
+    // whether decoded points are validated, i.e. unless checked by checksum
+    validate: Validate,
+}
+
+impl<E: Pairing> MappedSrs<E> {
+    // `u64` length prefix of vectors and maps
+    const LEN_SIZE: usize = 8;
+
+    /// Map the parameter file at `src`, and check its sha256sum is one of
+    /// `checksums`. If `None`, the checksum is skipped and every point is
+    /// validated instead as it's decoded.
+    pub fn open(src: impl AsRef<Path>, checksums: Option<&[[u8; 32]]>) -> Result<Self> {
+        let f = File::open(src.as_ref()).map_err(|e| Error::file(src.as_ref(), e))?;
+        // SAFETY: the file is only read, and the caller ensures it's not
+        // modified while mapped, as documented.
+        let mmap = unsafe { Mmap::map(&f)? };
+
+        if let Some(checksums) = checksums {
+            let checksum: [u8; 32] = Sha256::digest(&mmap[..]).into();
+            if !checksums.contains(&checksum) {
+                return Err(Error::ChecksumMismatch {
+                    path: src.as_ref().to_path_buf(),
+                });
+            }
+        }
+
+        let (header, payload) = SrsHeader::split(&mmap[..])?;
+        if let Some(header) = header {
+            if header.encoding != PointEncoding::Uncompressed
+                || CurveId::of::<E>() != Some(header.curve)
+            {
+                return Err(Error::MalformedCache(format!(
+                    "Only uncompressed {:?} SRS files can be mapped",
+                    CurveId::of::<E>()
+                )));
+            }
+            if Sha256::digest(payload)[..] != header.content_hash {
+                return Err(Error::MalformedCache(
+                    "SRS payload doesn't match its content hash".into(),
+                ));
+            }
+        }
+        let offset = mmap.len() - payload.len();
+
+        let g1_size = Self::g1_size();
+        let g2_size = E::G2Affine::generator().uncompressed_size();
+        let num_powers = u64::deserialize_uncompressed(payload)? as usize;
+        let expected_size = num_powers
+            .checked_mul(g1_size)
+            .and_then(|s| s.checked_add(3 * Self::LEN_SIZE + 2 * g2_size))
+            .ok_or_else(|| Error::MalformedCache("Invalid number of powers".into()))?;
+        if payload.len() != expected_size {
+            return Err(Error::MalformedCache(format!(
+                "Expect {expected_size} bytes for {num_powers} powers, got {}",
+                payload.len()
+            )));
+        }
+
+        let mut pos = offset + Self::LEN_SIZE + num_powers * g1_size;
+        let num_gamma_g = u64::deserialize_uncompressed(&mmap[pos..])?;
+        pos += Self::LEN_SIZE;
+        let h = E::G2Affine::deserialize_uncompressed(&mmap[pos..])?;
+        pos += g2_size;
+        let beta_h = E::G2Affine::deserialize_uncompressed(&mmap[pos..])?;
+        pos += g2_size;
+        let num_neg_h = u64::deserialize_uncompressed(&mmap[pos..])?;
+        if num_gamma_g != 0 || num_neg_h != 0 {
+            return Err(Error::MalformedCache(
+                "Only parameters without powers_of_gamma_g and neg_powers_of_h can be mapped"
+                    .into(),
+            ));
+        }
+
+        Ok(Self {
+            mmap,
+            offset,
+            num_powers,
+            h,
+            beta_h,
+            validate: if checksums.is_some() {
+                Validate::No
+            } else {
+                Validate::Yes
+            },
+        })
+    }
+
+    fn g1_size() -> usize {
+        E::G1Affine::generator().uncompressed_size()
+    }
+
+    /// Max degree supported, i.e. number of `powers_of_g` minus one
+    pub fn max_degree(&self) -> usize {
+        self.num_powers.saturating_sub(1)
+    }
+
+    /// The raw bytes of `powers_of_g[range]`, each point of fixed size in the
+    /// uncompressed encoding. An inverted range is empty.
+    pub fn powers_of_g_bytes(&self, range: Range<usize>) -> Result<&[u8]> {
+        if range.end > self.num_powers {
+            return Err(Error::DegreeOutOfRange {
+                degree: range.end - 1,
+                max: self.max_degree(),
+            });
+        }
+        if range.is_empty() {
+            return Ok(&[]);
+        }
+        let start = self.offset + Self::LEN_SIZE;
+        let g1_size = Self::g1_size();
+        Ok(&self.mmap[start + range.start * g1_size..start + range.end * g1_size])
+    }
+
+    /// Decode `powers_of_g[range]` from the mapped file. Points are only
+    /// validated if the file wasn't checked against a checksum on opening.
+    pub fn powers_of_g(&self, range: Range<usize>) -> Result<Vec<E::G1Affine>> {
+        let bytes = self.powers_of_g_bytes(range)?;
+        cfg_chunks!(bytes, Self::g1_size())
+            .map(|b| {
+                Ok(E::G1Affine::deserialize_with_mode(
+                    b,
+                    Compress::No,
+                    self.validate,
+                )?)
+            })
+            .collect()
+    }
+
+    /// The generator of G2
+    pub fn h(&self) -> E::G2Affine {
+        self.h
+    }
+
+    /// `beta` times the generator of G2
+    pub fn beta_h(&self) -> E::G2Affine {
+        self.beta_h
+    }
+
+    /// Decode KZG10 public parameters supporting polynomials up to `degree`
+    pub fn universal_params(&self, degree: usize) -> Result<UniversalParams<E>> {
+        if degree >= self.num_powers {
+            return Err(Error::DegreeOutOfRange {
+                degree,
+                max: self.max_degree(),
+            });
+        }
+        Ok(UniversalParams {
+            powers_of_g: self.powers_of_g(0..degree + 1)?,
+            powers_of_gamma_g: BTreeMap::new(),
+            h: self.h,
+            beta_h: self.beta_h,
+            neg_powers_of_h: BTreeMap::new(),
+            prepared_h: self.h.into(),
+            prepared_beta_h: self.beta_h.into(),
+        })
+    }
+}
+
+/// Hash tree over fixed-size chunks of a parameter file, stored next to it as
+/// a `.chunks` sidecar, so that any byte range can be verified by only hashing
+/// the chunks covering it, instead of the whole file.
+///
+/// The leaves are the sha256sum of each chunk, and the index also records the
+/// sha256sum of the whole file it was built from. It is only written next to
+/// files of a cache directory once they passed their checks, and only trusted
+/// if its root is pinned, e.g. by
+/// [`AZTEC20_CHUNK_ROOTS`](crate::constants::AZTEC20_CHUNK_ROOTS), see
+/// [`load_params_prefix()`](kzg10::load_params_prefix).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkIndex {
+    /// size of each chunk in bytes, except the last one
+    pub chunk_size: u64,
+    /// size of the indexed file in bytes
+    pub file_size: u64,
+    /// sha256sum of the whole indexed file
+    pub file_checksum: [u8; 32],
+    /// sha256sum of each chunk
+    pub leaves: Vec<[u8; 32]>,
+    /// root of the binary hash tree over `leaves`
+    pub root: [u8; 32],
+}
+
+impl ChunkIndex {
+    /// Default chunk size, 1 MiB
+    pub const DEFAULT_CHUNK_SIZE: u64 = 1 << 20;
+    const MAGIC: &'static [u8; 8] = b"ARKSRSCI";
+
+    /// Build the index of `bytes` over chunks of `chunk_size` bytes
+    pub fn build(bytes: &[u8], chunk_size: u64) -> Self {
+        let leaves: Vec<[u8; 32]> = bytes
+            .chunks(chunk_size as usize)
+            .map(|c| Sha256::digest(c).into())
+            .collect();
+        Self {
+            chunk_size,
+            file_size: bytes.len() as u64,
+            file_checksum: Sha256::digest(bytes).into(),
+            root: Self::compute_root(&leaves),
+            leaves,
+        }
+    }
+
+    // binary hash tree, an odd node is carried over to the next level as is
+    fn compute_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+        let mut level = leaves.to_vec();
+        if level.is_empty() {
+            return Sha256::digest([]).into();
+        }
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [l, r] => Sha256::new()
+                        .chain_update(l)
+                        .chain_update(r)
+                        .finalize()
+                        .into(),
+                    [l] => *l,
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        level[0]
+    }
+
+    /// The path of the sidecar index of `src`
+    pub fn sidecar_path(src: &Path) -> PathBuf {
+        let mut path = src.as_os_str().to_owned();
+        path.push(".chunks");
+        PathBuf::from(path)
+    }
+
+    /// Persist the index into `dest`, atomically replacing any previous one
+    pub fn write(&self, dest: &Path) -> Result<()> {
+        let mut bytes = Self::MAGIC.to_vec();
+        for v in [self.chunk_size, self.file_size, self.leaves.len() as u64] {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.file_checksum);
+        bytes.extend_from_slice(&self.root);
+        self.leaves.iter().for_each(|l| bytes.extend_from_slice(l));
+        let temp = Downloader::temp_path(dest);
+        fs::write(&temp, bytes).map_err(|e| Error::file(&temp, e))?;
+        fs::rename(&temp, dest).map_err(|e| Error::file(dest, e))
+    }
+
+    /// Read an index from `src`, checking it's consistent with its root
+    pub fn read(src: &Path) -> Result<Self> {
+        let malformed = |msg: &str| Error::MalformedCache(format!("{}: {msg}", src.display()));
+        let bytes = fs::read(src).map_err(|e| Error::file(src, e))?;
+        let (magic, rest) = bytes
+            .split_at_checked(8)
+            .ok_or_else(|| malformed("index too short"))?;
+        if magic != Self::MAGIC {
+            return Err(malformed("not a chunk index, invalid magic bytes"));
+        }
+        let (header, rest) = rest
+            .split_at_checked(24 + 64)
+            .ok_or_else(|| malformed("index too short"))?;
+        let field = |i: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&header[i * 8..(i + 1) * 8]);
+            u64::from_le_bytes(buf)
+        };
+        let digest = |bytes: &[u8]| {
+            let mut buf = [0u8; 32];
+            buf.copy_from_slice(bytes);
+            buf
+        };
+        let (chunk_size, file_size, num_leaves) = (field(0), field(1), field(2));
+        if chunk_size == 0
+            || file_size.div_ceil(chunk_size) != num_leaves
+            || num_leaves.checked_mul(32) != Some(rest.len() as u64)
+        {
+            return Err(malformed("inconsistent chunk index header"));
+        }
+        let index = Self {
+            chunk_size,
+            file_size,
+            file_checksum: digest(&header[24..56]),
+            root: digest(&header[56..88]),
+            leaves: rest.chunks_exact(32).map(digest).collect(),
+        };
+        if Self::compute_root(&index.leaves) != index.root {
+            return Err(malformed("corrupted chunk index"));
+        }
+        Ok(index)
+    }
+
+    /// Read `range` of bytes from `f`, verifying every chunk it spans
+    pub fn read_range(&self, f: &mut File, range: Range<u64>) -> Result<Vec<u8>> {
+        if range.start > range.end || range.end > self.file_size {
+            return Err(Error::MalformedCache(format!(
+                "Range {range:?} out of {} indexed bytes",
+                self.file_size
+            )));
+        }
+        if range.is_empty() {
+            return Ok(Vec::new());
+        }
+        let first = range.start / self.chunk_size;
+        let last = (range.end - 1) / self.chunk_size;
+        let start = first * self.chunk_size;
+        let end = ((last + 1) * self.chunk_size).min(self.file_size);
+
+        let mut bytes = vec![0u8; (end - start) as usize];
+        f.seek(SeekFrom::Start(start))?;
+        f.read_exact(&mut bytes)?;
+        for (i, (chunk, leaf)) in bytes
+            .chunks(self.chunk_size as usize)
+            .zip(&self.leaves[first as usize..=last as usize])
+            .enumerate()
+        {
+            if Sha256::digest(chunk)[..] != leaf[..] {
+                return Err(Error::MalformedCache(format!(
+                    "Chunk {} doesn't match its checksum",
+                    first as usize + i
+                )));
+            }
+        }
+        bytes.truncate((range.end - start) as usize);
+        bytes.drain(..(range.start - start) as usize);
+        Ok(bytes)
+    }
+}
+
+// Files whose whole content was hashed by this process, keyed by path, size
+// and modification time, so that they aren't hashed again
+static VERIFIED_FILES: Mutex<BTreeSet<(PathBuf, u64, Option<SystemTime>)>> =
+    Mutex::new(BTreeSet::new());
+
+fn verified_key(src: &Path, meta: &fs::Metadata) -> (PathBuf, u64, Option<SystemTime>) {
+    (src.to_path_buf(), meta.len(), meta.modified().ok())
+}
+
+// Whether `src` was hashed in full by this process, and not modified since
+pub(crate) fn is_verified(src: &Path, meta: &fs::Metadata) -> bool {
+    VERIFIED_FILES
+        .lock()
+        .is_ok_and(|files| files.contains(&verified_key(src, meta)))
+}
+
+pub(crate) fn mark_verified(src: &Path, meta: &fs::Metadata) {
+    if let Ok(mut files) = VERIFIED_FILES.lock() {
+        files.insert(verified_key(src, meta));
+    }
+}
+
+// The sha256sum of the whole file, streamed
+pub(crate) fn file_sha256(f: &mut File) -> Result<[u8; 32]> {
+    f.seek(SeekFrom::Start(0))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(f, &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
+/// Record of a parameter file moved into quarantine after failing its
+/// checksum, stored as json next to it. Files are never deleted, since a
+/// user-supplied one may be costly to regenerate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuarantineRecord {
+    /// where the file was
+    pub source: PathBuf,
+    /// hex-encoded pinned checksum of the file, e.g. its sha256sum
+    pub expected: String,
+    /// hex-encoded checksum of the file
+    pub actual: String,
+    /// seconds since the unix epoch
+    pub quarantined_at: u64,
+}
+
+impl QuarantineRecord {
+    /// The directory quarantining files of the same directory as `src`
+    pub fn quarantine_dir(src: &Path) -> PathBuf {
+        src.parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("quarantine")
+    }
+
+    /// The path of the record of the quarantined file `path`
+    pub fn sidecar_path(path: &Path) -> PathBuf {
+        let mut path = path.as_os_str().to_owned();
+        path.push(".json");
+        PathBuf::from(path)
+    }
+}
+
+// Move `src`, which failed its checksum, and its chunk index into quarantine,
+// under its lock, unless it was replaced since `read_meta` (e.g. by a
+// concurrent download). Returns where it was moved.
+pub(crate) fn quarantine(
+    src: &Path,
+    read_meta: &fs::Metadata,
+    expected: &[u8],
+    actual: &[u8],
+) -> Result<Option<PathBuf>> {
+    let _lock = CacheLock::exclusive(src)?;
+    match fs::metadata(src) {
+        Ok(meta)
+            if meta.len() == read_meta.len()
+                && meta.modified().ok() == read_meta.modified().ok() => {},
+        _ => {
+            tracing::warn!(
+                "Checksum failed, but {} was replaced meanwhile",
+                src.display()
+            );
+            return Ok(None);
+        },
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let quarantined_at = now.as_secs();
+    let dir = QuarantineRecord::quarantine_dir(src);
+    fs::create_dir_all(&dir).map_err(|e| Error::file(&dir, e))?;
+    // unique even if the same entry is quarantined again right away
+    let dest = (0u32..)
+        .map(|i| {
+            let mut name = src.file_name().unwrap_or_default().to_owned();
+            name.push(format!(".{quarantined_at}.{:09}", now.subsec_nanos()));
+            if i > 0 {
+                name.push(format!(".{i}"));
+            }
+            dir.join(name)
+        })
+        .find(|dest| !dest.exists())
+        .unwrap_or_default();
+
+    tracing::error!(
+        "Checksum failed, moving {} to {}",
+        src.display(),
+        dest.display()
+    );
+    fs::rename(src, &dest).map_err(|e| Error::file(src, e))?;
+    let index = ChunkIndex::sidecar_path(src);
+    if index.exists() {
+        fs::rename(&index, ChunkIndex::sidecar_path(&dest)).map_err(|e| Error::file(&index, e))?;
+    }
+    let record = QuarantineRecord {
+        source: src.to_path_buf(),
+        expected: hex::encode(expected),
+        actual: hex::encode(actual),
+        quarantined_at,
+    };
+    let sidecar = QuarantineRecord::sidecar_path(&dest);
+    // serializing plain strings and integers never fails
+    let json = serde_json::to_vec_pretty(&record).unwrap_or_default();
+    fs::write(&sidecar, json).map_err(|e| Error::file(&sidecar, e))?;
+    Ok(Some(dest))
+}
+
+// Write the chunk index of `src`, a file of the cache verified with content
+// `bytes` and sha256sum `checksum`, under its lock, unless it has a valid index
+// already or was replaced since `read_meta`
+fn index_cached(
+    src: &Path,
+    read_meta: &fs::Metadata,
+    bytes: &[u8],
+    checksum: &[u8; 32],
+) -> Result<()> {
+    let _lock = CacheLock::exclusive(src)?;
+    match fs::metadata(src) {
+        Ok(meta)
+            if meta.len() == read_meta.len()
+                && meta.modified().ok() == read_meta.modified().ok() => {},
+        _ => return Ok(()),
+    }
+    let sidecar = ChunkIndex::sidecar_path(src);
+    if ChunkIndex::read(&sidecar).is_ok_and(|index| index.file_checksum == *checksum) {
+        return Ok(());
+    }
+    ChunkIndex::build(bytes, ChunkIndex::DEFAULT_CHUNK_SIZE).write(&sidecar)
+}
+
 /// Download srs file and save to disk
 ///
 /// - `basename`: the filename used in download URL
 /// - `dest`: the filename for local cache
 pub fn download_srs_file(basename: &str, dest: impl AsRef<Path>) -> Result<()> {
-    // Ensure download directory exists
-    create_dir_all(dest.as_ref().parent().context("no parent dir")?)
-        .context("Unable to create directory")?;
+    download_from_mirrors(&SrsConfig::default().release_urls(basename), dest)
+}
 
-    let version = "0.2.0"; // TODO infer or make configurable
-    let url = format!(
-        "https://github.com/EspressoSystems/ark-srs/releases/download/v{version}/{basename}",
-    );
-    tracing::info!("Downloading SRS from {url}");
-    let mut buf: Vec<u8> = Vec::new();
-    ureq::get(&url)
-        .call()?
-        .into_reader()
-        .read_to_end(&mut buf)?;
-
-    // Download to a temporary file and rename to dest on completion. This
-    // should prevent some errors if this function is called concurrently
-    // because the concurrent operations would happen on different files and the
-    // destination file should never be in an incomplete state.
-    let mut temp_path = dest.as_ref().as_os_str().to_owned();
-    let suffix: String = rand::thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(16)
-        .map(char::from)
-        .collect();
-    temp_path.push(format!(".temp.{suffix}"));
-    {
-        let mut f = File::create(&temp_path)?;
-        f.write_all(&buf)?;
-    }
-    std::fs::rename(temp_path, dest.as_ref())?;
-    tracing::info!("Saved SRS to {:?}", dest.as_ref());
+/// Download the same file from each of `urls` in turn, until one succeeds.
+/// Returns the error of the last attempt if all of them fail.
+pub fn download_from_mirrors(urls: &[String], dest: impl AsRef<Path>) -> Result<()> {
+    Downloader::default().download_from_mirrors(urls, dest.as_ref(), None)?;
     Ok(())
 }
 
+/// Download the file at `url` (`http(s)://` or `file://`) and save to `dest`,
+/// see [`Downloader`]
+pub fn download_file(url: &str, dest: impl AsRef<Path>) -> Result<()> {
+    Downloader::default().download(url, dest.as_ref(), None)?;
+    Ok(())
+}
+
+// `download_file()`, unless `dest` was downloaded meanwhile by another process
+pub(crate) fn fetch_file(url: &str, dest: impl AsRef<Path>) -> Result<()> {
+    Downloader::default().fetch_from_mirrors(&[url.into()], dest.as_ref(), None)
+}
+
 /// The base data directory for the project
-fn get_project_root() -> Result<PathBuf> {
+pub(crate) fn get_project_root() -> Result<PathBuf> {
     // (empty) qualifier, (empty) organization, and application name
     // see more <https://docs.rs/directories/5.0.1/directories/struct.ProjectDirs.html#method.from>
     Ok(ProjectDirs::from("", "", "ark-srs")
-        .context("Failed to get project root")?
+        .ok_or(Error::NoProjectRoot)?
         .data_dir()
         .to_path_buf())
 }
@@ -90,8 +901,339 @@ fn get_project_root() -> Result<PathBuf> {
 /// loading KZG10 parameters from files
 pub mod kzg10 {
     use super::*;
+    use crate::{ceremony::Ceremony, config::Decompression};
     use ark_poly_commit::kzg10;
 
+    /// Returns the default path for the pre-serialized param file of `C` of
+    /// `degree`, with points in `encoding`
+    pub fn artifact_path<C: Ceremony>(
+        project_root: Option<PathBuf>,
+        degree: usize,
+        encoding: PointEncoding,
+    ) -> Result<PathBuf> {
+        let mut path = if let Some(root) = project_root {
+            root
+        } else {
+            get_project_root()?
+        };
+        path.push(C::NAME);
+        path.push(C::artifact_name(degree, encoding));
+        Ok(path)
+    }
+
+    /// Write a copy of the (uncompressed) parameter file `src` of `C` into
+    /// `dest` as a self-describing file with compressed points, see
+    /// [`SrsHeader`]. The header records the sha256sum of `src` as its
+    /// source, so that the copy is reproducible from `src` alone.
+    pub fn compress_params<C: Ceremony>(src: PathBuf, dest: PathBuf) -> Result<()> {
+        let bytes = fs::read(&src).map_err(|e| Error::file(&src, e))?;
+        let (header, pp) =
+            decode_srs::<C::Pairing>(&bytes, false).map_err(|e| e.context(src.display()))?;
+        if header.is_some_and(|h| h.encoding == PointEncoding::Compressed) {
+            return Err(Error::MalformedCache(format!(
+                "{} is already compressed",
+                src.display()
+            )));
+        }
+        let header = SrsHeader::kzg10(C::CURVE, C::NAME, PointEncoding::Compressed)
+            .source_hashes([Sha256::digest(&bytes)]);
+        store_srs(&pp, header, &dest)
+    }
+
+    /// Load KZG10 parameters of ceremony `C` from a pre-serialized file, with
+    /// uncompressed or compressed points, checked against the pinned
+    /// checksums of `C`.
+    ///
+    /// # Note
+    /// we force specifying a `src` (instead of taking in `Option`) in
+    /// case the param files contains much more than `degree` needed.
+    /// See [`CacheResolver`](crate::cache::CacheResolver) to find the
+    /// smallest cached file covering the degree requested.
+    pub fn load_params<C: Ceremony>(
+        degree: usize,
+        src: PathBuf,
+    ) -> Result<kzg10::UniversalParams<C::Pairing>> {
+        load_params_with::<C>(
+            degree,
+            src,
+            &C::checksums(),
+            TrustPolicy::Pinned,
+            Decompression::Unchecked,
+            false,
+        )
+    }
+
+    // Whether a file of `f_degree` with sha256sum `checksum` is accepted,
+    // returns `Ok(true)` if it's only trusted by `policy`, thus needs
+    // to be checked.
+    fn check_trust(
+        src: &Path,
+        f_degree: usize,
+        checksum: &[u8; 32],
+        checksums: &[(usize, [u8; 32])],
+        policy: TrustPolicy,
+    ) -> Result<bool> {
+        let mut pinned = checksums.iter().filter(|(d, _)| *d == f_degree).peekable();
+        if pinned.peek().is_none() {
+            return match policy {
+                TrustPolicy::Custom => Ok(true),
+                TrustPolicy::Pinned => Err(Error::UntrustedFile {
+                    path: src.to_path_buf(),
+                }),
+            };
+        }
+        if pinned.any(|(_, cksum)| cksum == checksum) {
+            Ok(false)
+        } else {
+            Err(Error::ChecksumMismatch {
+                path: src.to_path_buf(),
+            })
+        }
+    }
+
+    // `load_params()` against the `(degree, sha256sum)` of a release,
+    // checking points as per `decompression`, and indexing `src` if `cached`
+    // in a cache directory
+    pub(crate) fn load_params_with<C: Ceremony>(
+        degree: usize,
+        src: PathBuf,
+        checksums: &[(usize, [u8; 32])],
+        policy: TrustPolicy,
+        decompression: Decompression,
+        cached: bool,
+    ) -> Result<kzg10::UniversalParams<C::Pairing>> {
+        let mut f = File::open(&src).map_err(|e| Error::file(&src, e))?;
+        let read_meta = f.metadata().map_err(|e| Error::file(&src, e))?;
+        let mut bytes = Vec::with_capacity(read_meta.len() as usize);
+        f.read_to_end(&mut bytes)
+            .map_err(|e| Error::file(&src, e))?;
+
+        // the max degree of the param file supported, from its header
+        // or the length prefix of `powers_of_g` rather than the file
+        // name, so that files can be named freely
+        let (header, payload) = SrsHeader::split(&bytes).map_err(|e| e.context(src.display()))?;
+        let f_degree = match header {
+            Some(header) if header.ceremony != C::NAME => {
+                return Err(Error::MalformedCache(format!(
+                    "{} holds parameters of {}",
+                    src.display(),
+                    header.ceremony
+                )));
+            },
+            Some(header) => header.degree,
+            None => (u64::deserialize_uncompressed(payload)? as usize)
+                .checked_sub(1)
+                .ok_or_else(|| Error::MalformedCache(format!("{} is empty", src.display())))?,
+        };
+        if degree > f_degree {
+            return Err(Error::DegreeOutOfRange {
+                degree,
+                max: f_degree,
+            });
+        }
+
+        let checksum: [u8; 32] = Sha256::digest(&bytes).into();
+        let custom = match check_trust(&src, f_degree, &checksum, checksums, policy) {
+            Err(Error::ChecksumMismatch { path }) => {
+                if let Some((_, expected)) = checksums.iter().find(|(d, _)| *d == f_degree) {
+                    quarantine(&path, &read_meta, expected, &checksum)?;
+                }
+                return Err(Error::ChecksumMismatch { path });
+            },
+            res => res?,
+        };
+
+        if custom {
+            tracing::warn!("Checking all points of custom file {}", src.display());
+        }
+        let validate = custom || decompression == Decompression::Checked;
+        let (_, mut srs) =
+            decode_srs::<C::Pairing>(&bytes, validate).map_err(|e| e.context(src.display()))?;
+
+        // index the verified file, so that the cache finds it without hashing
+        // it, and only the needed prefix is loaded next time if its root is
+        // pinned. Never next to files supplied by the caller.
+        if cached {
+            if let Err(e) = index_cached(&src, &read_meta, &bytes, &checksum) {
+                tracing::warn!("Failed to index {}: {e}", src.display());
+            }
+        }
+
+        // trim the srs to fit the actual requested degree
+        srs.powers_of_g.truncate(degree + 1);
+        Ok(srs)
+    }
+
+    /// Load KZG10 parameters of ceremony `C`, only reading and verifying the
+    /// first `degree + 1` G1 powers and the remaining parameters of `src`,
+    /// see [`ChunkIndex`].
+    ///
+    /// The index of `src` is only trusted if its root is pinned by
+    /// [`Ceremony::chunk_roots()`]. Falls back to [`load_params()`], hashing
+    /// the whole file, otherwise.
+    pub fn load_params_prefix<C: Ceremony>(
+        degree: usize,
+        src: PathBuf,
+    ) -> Result<kzg10::UniversalParams<C::Pairing>> {
+        load_params_prefix_with::<C>(
+            degree,
+            src,
+            &C::checksums(),
+            &C::chunk_roots(),
+            TrustPolicy::Pinned,
+            Decompression::Unchecked,
+            false,
+        )
+    }
+
+    // `load_params_prefix()` against the `(degree, sha256sum)` and the
+    // `(sha256sum, chunk index root)` of a release, checking points as per
+    // `decompression`, and indexing `src` if `cached` in a cache directory
+    pub(crate) fn load_params_prefix_with<C: Ceremony>(
+        degree: usize,
+        src: PathBuf,
+        checksums: &[(usize, [u8; 32])],
+        chunk_roots: &[([u8; 32], [u8; 32])],
+        policy: TrustPolicy,
+        decompression: Decompression,
+        cached: bool,
+    ) -> Result<kzg10::UniversalParams<C::Pairing>> {
+        let full_load =
+            |src| load_params_with::<C>(degree, src, checksums, policy, decompression, cached);
+        // the index is only trusted if pinned, as anyone writing the file
+        // could write its index as well
+        let index = match ChunkIndex::read(&ChunkIndex::sidecar_path(&src)) {
+            Ok(index) if chunk_roots.contains(&(index.file_checksum, index.root)) => index,
+            _ => return full_load(src),
+        };
+        let mut f = File::open(&src).map_err(|e| Error::file(&src, e))?;
+        let meta = f.metadata().map_err(|e| Error::file(&src, e))?;
+        if meta.len() != index.file_size {
+            tracing::warn!("{} doesn't match its index, reindexing it", src.display());
+            return full_load(src);
+        }
+
+        // corrupted since indexed: quarantined so that it's fetched again,
+        // as done by `load_params_with()` for a mismatching checksum
+        let res = load_indexed::<C>(
+            degree,
+            &src,
+            (&mut f, &meta),
+            &index,
+            checksums,
+            policy,
+            decompression,
+        );
+        match res {
+            Err(
+                e @ (Error::MalformedCache(_) | Error::Serialization(_) | Error::InvalidPoint(_)),
+            ) => {
+                tracing::error!("{} is corrupted: {e}", src.display());
+                let actual = file_sha256(&mut f)?;
+                quarantine(&src, &meta, &index.file_checksum, &actual)?;
+                Err(Error::ChecksumMismatch { path: src })
+            },
+            res => res,
+        }
+    }
+
+    // `load_params_prefix_with()` from the verified `index` of `src`, opened
+    // as `f` with metadata `meta`
+    fn load_indexed<C: Ceremony>(
+        degree: usize,
+        src: &Path,
+        (f, meta): (&mut File, &fs::Metadata),
+        index: &ChunkIndex,
+        checksums: &[(usize, [u8; 32])],
+        policy: TrustPolicy,
+        decompression: Decompression,
+    ) -> Result<kzg10::UniversalParams<C::Pairing>> {
+        const LEN_SIZE: u64 = 8;
+        let (header, offset) = SrsHeader::read_layout(|range| index.read_range(f, range))
+            .map_err(|e| e.context(src.display()))?;
+        if let Some(header) = &header {
+            if header.ceremony != C::NAME || header.curve != C::CURVE {
+                return Err(Error::MalformedCache(format!(
+                    "{} holds parameters of {} over {:?}",
+                    src.display(),
+                    header.ceremony,
+                    header.curve
+                )));
+            }
+        }
+        let compress = header
+            .as_ref()
+            .map_or(Compress::No, |header| header.encoding.into());
+        let g1_size =
+            <C::Pairing as Pairing>::G1Affine::generator().serialized_size(compress) as u64;
+        let num_powers =
+            u64::deserialize_uncompressed(&index.read_range(f, offset..offset + LEN_SIZE)?[..])?;
+        let f_degree = num_powers
+            .checked_sub(1)
+            .ok_or_else(|| Error::MalformedCache(format!("{} is empty", src.display())))?
+            as usize;
+        if header.is_some_and(|header| header.degree != f_degree) {
+            return Err(Error::MalformedCache(format!(
+                "{} doesn't match its header",
+                src.display()
+            )));
+        }
+        let custom = match check_trust(src, f_degree, &index.file_checksum, checksums, policy) {
+            Err(Error::ChecksumMismatch { path }) => {
+                if let Some((_, expected)) = checksums.iter().find(|(d, _)| *d == f_degree) {
+                    quarantine(&path, meta, expected, &index.file_checksum)?;
+                }
+                return Err(Error::ChecksumMismatch { path });
+            },
+            res => res?,
+        };
+        if degree > f_degree {
+            return Err(Error::DegreeOutOfRange {
+                degree,
+                max: f_degree,
+            });
+        }
+
+        let validate = if custom {
+            Validate::Yes
+        } else {
+            decompression.into()
+        };
+        let start = offset + LEN_SIZE;
+        let prefix = index.read_range(f, start..start + (degree as u64 + 1) * g1_size)?;
+        let powers_of_g = cfg_chunks!(prefix, g1_size as usize)
+            .map(|b| {
+                <C::Pairing as Pairing>::G1Affine::deserialize_with_mode(b, compress, validate)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // powers_of_gamma_g, h, beta_h, neg_powers_of_h
+        let tail = index.read_range(f, start + num_powers * g1_size..index.file_size)?;
+        let mut reader = &tail[..];
+        let powers_of_gamma_g = BTreeMap::deserialize_with_mode(&mut reader, compress, validate)?;
+        let h = <C::Pairing as Pairing>::G2Affine::deserialize_with_mode(
+            &mut reader,
+            compress,
+            validate,
+        )?;
+        let beta_h = <C::Pairing as Pairing>::G2Affine::deserialize_with_mode(
+            &mut reader,
+            compress,
+            validate,
+        )?;
+        let neg_powers_of_h = BTreeMap::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        Ok(kzg10::UniversalParams {
+            powers_of_g,
+            powers_of_gamma_g,
+            h,
+            beta_h,
+            neg_powers_of_h,
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+        })
+    }
+
     /// ceremonies for curve [Bn254][https://docs.rs/ark-bn254/latest/ark_bn254/]
     pub mod bn254 {
         use super::*;
@@ -99,74 +1241,710 @@ pub mod kzg10 {
 
         /// Aztec2020 KZG setup
         pub mod aztec {
-            use crate::constants::AZTEC20_CHECKSUMS;
+            use crate::{constants::AZTEC20_CHECKSUMS, kzg10::aztec20::Aztec20};
 
             use super::*;
 
             /// Returns the default path for pre-serialized param files
             pub fn default_path(project_root: Option<PathBuf>, degree: usize) -> Result<PathBuf> {
+                artifact_path::<Aztec20>(project_root, degree, PointEncoding::Uncompressed)
+            }
+
+            /// Returns the default path for pre-serialized param files with
+            /// compressed points, see [`compress_aztec_srs()`]
+            pub fn compressed_path(
+                project_root: Option<PathBuf>,
+                degree: usize,
+            ) -> Result<PathBuf> {
+                artifact_path::<Aztec20>(project_root, degree, PointEncoding::Compressed)
+            }
+
+            /// Write a copy of the (uncompressed) parameter file `src` into
+            /// `dest` with compressed points, see [`compress_params()`]
+            pub fn compress_aztec_srs(src: PathBuf, dest: PathBuf) -> Result<()> {
+                compress_params::<Aztec20>(src, dest)
+            }
+
+            /// Memory-map a pre-serialized parameter file of Aztec's ignition
+            /// ceremony, checking its checksum against [`AZTEC20_CHECKSUMS`].
+            /// See [`MappedSrs`].
+            pub fn map_aztec_srs(src: PathBuf) -> Result<MappedSrs<Bn254>> {
+                let checksums: Vec<[u8; 32]> = AZTEC20_CHECKSUMS.iter().map(|(_, c)| *c).collect();
+                let srs = MappedSrs::open(&src, Some(&checksums))?;
+                if !AZTEC20_CHECKSUMS
+                    .iter()
+                    .any(|(d, _)| *d == srs.max_degree())
+                {
+                    return Err(Error::UnsupportedDegree {
+                        degree: srs.max_degree(),
+                        supported: AZTEC20_CHECKSUMS.iter().map(|(d, _)| *d).collect(),
+                    });
+                }
+                Ok(srs)
+            }
+
+            /// Load SRS from Aztec's ignition ceremony from files, see
+            /// [`load_params()`].
+            pub fn load_aztec_srs(
+                degree: usize,
+                src: PathBuf,
+            ) -> Result<kzg10::UniversalParams<Bn254>> {
+                load_params::<Aztec20>(degree, src)
+            }
+
+            /// Load SRS from Aztec's ignition ceremony, only reading and
+            /// verifying the first `degree + 1` G1 points and the G2 points of
+            /// `src`, see [`load_params_prefix()`].
+            pub fn load_aztec_srs_prefix(
+                degree: usize,
+                src: PathBuf,
+            ) -> Result<kzg10::UniversalParams<Bn254>> {
+                load_params_prefix::<Aztec20>(degree, src)
+            }
+        }
+
+        /// barretenberg and Noir's `bb` CRS directory, i.e. `bn254_g1.dat` and
+        /// `bn254_g2.dat` of Aztec's ignition points
+        pub mod barretenberg {
+            use super::*;
+            use crate::{
+                config::SrsConfig,
+                kzg10::barretenberg::{BbCrs, G1_BASENAME, G2_BASENAME},
+                verify::kzg10::verify_powers,
+            };
+
+            /// Returns the default CRS directory of barretenberg, i.e.
+            /// `~/.bb-crs`
+            pub fn default_dir() -> Result<PathBuf> {
+                let home = directories::BaseDirs::new().ok_or(Error::NoProjectRoot)?;
+                Ok(home.home_dir().join(".bb-crs"))
+            }
+
+            /// Load SRS of `degree` from the `bn254_g1.dat` and `bn254_g2.dat`
+            /// files under `dir`, e.g. as downloaded by `bb`, see
+            /// [`BbCrs::read()`].
+            ///
+            /// These files have no checksum of their own: under
+            /// [`TrustPolicy::Pinned`], their points must be those of the
+            /// pinned artifact of Aztec's ignition covering `degree` (served
+            /// by [`aztec20::setup()`](crate::kzg10::aztec20::setup), which may
+            /// download it). Under [`TrustPolicy::Custom`], all their points
+            /// are checked instead.
+            pub fn load_bb_srs(
+                degree: usize,
+                dir: PathBuf,
+                policy: TrustPolicy,
+            ) -> Result<kzg10::UniversalParams<Bn254>> {
+                load_bb_srs_with_config(degree, dir, &SrsConfig::default().trust(policy))
+            }
+
+            /// Same as [`load_bb_srs()`], under the trust policy of `cfg`, and
+            /// with the pinned artifact served as configured in `cfg`.
+            pub fn load_bb_srs_with_config(
+                degree: usize,
+                dir: PathBuf,
+                cfg: &SrsConfig,
+            ) -> Result<kzg10::UniversalParams<Bn254>> {
+                let g1_path = dir.join(G1_BASENAME);
+                let g2_path = dir.join(G2_BASENAME);
+                let g1 = File::open(&g1_path).map_err(|e| Error::file(&g1_path, e))?;
+                let g2 = File::open(&g2_path).map_err(|e| Error::file(&g2_path, e))?;
+                let file_size = g1.metadata().map_err(|e| Error::file(&g1_path, e))?.len();
+                let num_points =
+                    BbCrs::num_points(file_size).map_err(|e| e.context(g1_path.display()))?;
+                if degree >= num_points {
+                    return Err(Error::DegreeOutOfRange {
+                        degree,
+                        max: num_points.saturating_sub(1),
+                    });
+                }
+                let crs = BbCrs::read(&mut BufReader::new(g1), &mut BufReader::new(g2), degree + 1)
+                    .map_err(|e| e.context(dir.display()))?;
+                let pp = crs.into_universal_params(degree)?;
+                match cfg.trust {
+                    TrustPolicy::Pinned => {
+                        let pinned = crate::kzg10::aztec20::setup_with_config(degree, cfg)?;
+                        if pp.powers_of_g != pinned.powers_of_g {
+                            return Err(Error::ChecksumMismatch { path: g1_path });
+                        }
+                        if pp.beta_h != pinned.beta_h {
+                            return Err(Error::ChecksumMismatch { path: g2_path });
+                        }
+                    },
+                    TrustPolicy::Custom => verify_powers(&pp)?,
+                }
+                Ok(pp)
+            }
+
+            /// Export KZG10 public parameters to `bn254_g1.dat` and
+            /// `bn254_g2.dat` files under `dir`, creating it if needed, see
+            /// [`BbCrs::from_universal_params()`].
+            pub fn store_bb_srs(pp: &kzg10::UniversalParams<Bn254>, dir: PathBuf) -> Result<()> {
+                let crs = BbCrs::from_universal_params(pp)?;
+                fs::create_dir_all(&dir).map_err(|e| Error::file(&dir, e))?;
+                let g1_path = dir.join(G1_BASENAME);
+                let g2_path = dir.join(G2_BASENAME);
+                let g1 = File::create(&g1_path).map_err(|e| Error::file(&g1_path, e))?;
+                let g2 = File::create(&g2_path).map_err(|e| Error::file(&g2_path, e))?;
+                let (mut g1, mut g2) = (std::io::BufWriter::new(g1), std::io::BufWriter::new(g2));
+                crs.write(&mut g1, &mut g2)?;
+                g1.flush()?;
+                Ok(g2.flush()?)
+            }
+
+            /// Export the pre-serialized parameter file `src` of Aztec's
+            /// ignition ceremony (e.g. from the cache, see
+            /// [`aztec::default_path()`](super::aztec::default_path)) up to
+            /// `degree` to `bn254_g1.dat` and `bn254_g2.dat` files under `dir`,
+            /// so that barretenberg shares the same download.
+            pub fn export_aztec_srs(degree: usize, src: PathBuf, dir: PathBuf) -> Result<()> {
+                let pp = super::aztec::load_aztec_srs(degree, src)?;
+                store_bb_srs(&pp, dir)
+            }
+        }
+
+        /// Perpetual Powers of Tau, as snarkjs `.ptau` files from Hermez
+        pub mod ppot {
+            use super::*;
+            use crate::{
+                constants::PPOT_BLAKE2B_CHECKSUMS,
+                kzg10::ppot::{PartialPtau, Ptau},
+                verify::kzg10::verify_powers,
+            };
+            use blake2::Blake2b512;
+
+            /// Returns the default path for the `.ptau` file of `power`
+            pub fn default_path(project_root: Option<PathBuf>, power: u32) -> Result<PathBuf> {
                 let mut path = if let Some(root) = project_root {
                     root
                 } else {
                     get_project_root()?
                 };
-                path.push("aztec20");
-                path.push(degree_to_basename(degree));
-                path.set_extension("bin");
+                path.push("ppot");
+                path.push(basename(power));
                 Ok(path)
             }
 
-            pub(crate) fn degree_to_basename(degree: usize) -> String {
-                format!("kzg10-aztec20-srs-{degree}.bin").to_string()
+            /// Filename of the `.ptau` file of `power` published by Hermez
+            pub fn basename(power: u32) -> String {
+                format!("powersOfTau28_hez_final_{power:02}.ptau")
             }
 
-            /// Load SRS from Aztec's ignition ceremony from files.
-            ///
-            /// # Note
-            /// we force specifying a `src` (instead of taking in `Option`) in
-            /// case the param files contains much more than `degree` needed.
-            /// And we want to avoid unnecessarily complicated logic for
-            /// iterating through all parameter files and find the smallest
-            /// param files that's bigger than the degree requested.
-            pub fn load_aztec_srs(
+            /// Load the first `degree + 1` powers of tau (and all other
+            /// phase-1 sections truncated to the same length) from a `.ptau`
+            /// file, without checking its hash.
+            pub fn load_ppot_srs(degree: usize, src: PathBuf) -> Result<Ptau> {
+                let f = File::open(&src).map_err(|e| Error::file(&src, e))?;
+                let mut reader = BufReader::new(f);
+                Ptau::read(&mut reader, Some(degree + 1))
+            }
+
+            /// The BLAKE2b hash of the file at `src`, streamed as `.ptau`
+            /// files grow up to tens of GB
+            pub fn ptau_blake2b(src: &Path) -> Result<[u8; 64]> {
+                let f = File::open(src).map_err(|e| Error::file(src, e))?;
+                let mut hasher = Blake2b512::new();
+                std::io::copy(&mut BufReader::new(f), &mut hasher)
+                    .map_err(|e| Error::file(src, e))?;
+                Ok(hasher.finalize().into())
+            }
+
+            // `load_ppot_srs()`, checking the whole file against the hash
+            // pinned for its power in `PPOT_BLAKE2B_CHECKSUMS`, moving it into
+            // quarantine if it doesn't match. A power without pinned hash is
+            // rejected, unless trusted by `policy`, in which case the powers
+            // loaded are checked instead.
+            pub(crate) fn load_ppot_srs_with(
                 degree: usize,
                 src: PathBuf,
-            ) -> Result<kzg10::UniversalParams<Bn254>> {
-                let mut f = File::open(&src).map_err(|_| anyhow!("{} not found", src.display()))?;
-                // the max degree of the param file supported, parsed from file name
-                // getting the 1024 out of `data/aztec20/kzg10-aztec20-srs-1024.bin`
-                let f_degree = src
-                    .file_stem()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .rsplit_once('-')
-                    .expect("unconventional filename")
-                    .1
-                    .parse::<usize>()
-                    .expect("fail to parse to uint");
-
-                let mut bytes = Vec::new();
-                f.read_to_end(&mut bytes)?;
+                policy: TrustPolicy,
+            ) -> Result<Ptau> {
+                let read_meta = fs::metadata(&src).map_err(|e| Error::file(&src, e))?;
+                let checksum = ptau_blake2b(&src)?;
+                let ptau = load_ppot_srs(degree, src.clone())?;
+                let power = ptau.header.power;
+                match PPOT_BLAKE2B_CHECKSUMS.iter().find(|(p, _)| *p == power) {
+                    Some((_, expected)) if *expected != checksum => {
+                        quarantine(&src, &read_meta, expected, &checksum)?;
+                        Err(Error::ChecksumMismatch { path: src })
+                    },
+                    Some(_) => Ok(ptau),
+                    None if policy == TrustPolicy::Pinned => {
+                        Err(Error::UntrustedFile { path: src })
+                    },
+                    None => {
+                        tracing::warn!(
+                            "Checking all loaded points of custom file {}",
+                            src.display()
+                        );
+                        let max_degree = ptau.tau_g1.len().saturating_sub(1);
+                        verify_powers(&ptau.clone().into_universal_params(max_degree)?)
+                            .map_err(|e| e.context(src.display()))?;
+                        Ok(ptau)
+                    },
+                }
+            }
+
+            /// Export KZG10 public parameters (e.g. from Aztec's ignition) to
+            /// a partial, non-snarkjs `.ptau` file at `dest`, see
+            /// [`PartialPtau`].
+            pub fn store_partial_ptau(
+                pp: &kzg10::UniversalParams<Bn254>,
+                dest: PathBuf,
+            ) -> Result<()> {
+                let ptau = PartialPtau::from_universal_params(pp)?;
+                let f = File::create(&dest).map_err(|e| Error::file(&dest, e))?;
+                let mut writer = std::io::BufWriter::new(f);
+                ptau.write(&mut writer)?;
+                Ok(writer.flush()?)
+            }
+        }
+
+        /// halo2 `ParamsKZG<Bn256>` files, e.g. `kzg_bn254_{k}.srs`
+        pub mod halo2 {
+            use super::*;
+            use crate::kzg10::halo2::{ParamsKzg, SerdeFormat};
+
+            /// Returns the default path for the params file of `k`
+            pub fn default_path(project_root: Option<PathBuf>, k: u32) -> Result<PathBuf> {
+                let mut path = if let Some(root) = project_root {
+                    root
+                } else {
+                    get_project_root()?
+                };
+                path.push("halo2");
+                path.push(basename(k));
+                Ok(path)
+            }
+
+            /// Filename of the params file of `k`, as published by PSE and
+            /// Axiom
+            pub fn basename(k: u32) -> String {
+                format!("kzg_bn254_{k}.srs")
+            }
+
+            /// Load halo2 params written in `format` from `src`, see
+            /// [`ParamsKzg::read()`].
+            pub fn load_halo2_params(
+                src: PathBuf,
+                format: SerdeFormat,
+                max_points: Option<usize>,
+                lagrange: bool,
+            ) -> Result<ParamsKzg> {
+                let f = File::open(&src).map_err(|e| Error::file(&src, e))?;
+                let mut reader = BufReader::new(f);
+                ParamsKzg::read(&mut reader, format, max_points, lagrange)
+                    .map_err(|e| e.context(src.display()))
+            }
+
+            /// Export KZG10 public parameters (e.g. from Aztec's ignition) to
+            /// halo2 params of `2^k` powers written in `format` at `dest`, see
+            /// [`ParamsKzg::from_universal_params()`].
+            pub fn store_halo2_params(
+                pp: &kzg10::UniversalParams<Bn254>,
+                k: u32,
+                dest: PathBuf,
+                format: SerdeFormat,
+            ) -> Result<()> {
+                let params = ParamsKzg::from_universal_params(pp, k)?;
+                let f = File::create(&dest).map_err(|e| Error::file(&dest, e))?;
+                let mut writer = std::io::BufWriter::new(f);
+                params.write(&mut writer, format)?;
+                Ok(writer.flush()?)
+            }
+        }
+    }
+
+    /// ceremonies for curve [Bls12-381][https://docs.rs/ark-bls12-381/latest/ark_bls12_381/]
+    pub mod bls12_381 {
+        use super::*;
+
+        /// Ethereum's EIP-4844 KZG ceremony
+        pub mod eth4844 {
+            use super::*;
+            use crate::{constants::ETH4844_CHECKSUMS, kzg10::eth4844::TrustedSetup};
+
+            /// Filename of the ceremony output, as published by `c-kzg-4844`
+            pub const BASENAME: &str = "trusted_setup.txt";
+
+            /// Returns the default path for the ceremony output file
+            pub fn default_path(project_root: Option<PathBuf>) -> Result<PathBuf> {
+                let mut path = if let Some(root) = project_root {
+                    root
+                } else {
+                    get_project_root()?
+                };
+                path.push("eth4844");
+                path.push(BASENAME);
+                Ok(path)
+            }
+
+            /// Load the EIP-4844 ceremony output from `src`, a
+            /// `trusted_setup.txt` file whose sha256sum is listed
+            /// in [`ETH4844_CHECKSUMS`]. A mismatching file is moved into
+            /// quarantine, see [`QuarantineRecord`].
+            pub fn load_eth4844_srs(src: PathBuf) -> Result<TrustedSetup> {
+                let read_meta = fs::metadata(&src).map_err(|e| Error::file(&src, e))?;
+                let bytes = fs::read(&src).map_err(|e| Error::file(&src, e))?;
 
                 let checksum: [u8; 32] = Sha256::digest(&bytes).into();
-                if !AZTEC20_CHECKSUMS
+                if !ETH4844_CHECKSUMS
                     .iter()
-                    .any(|(d, cksum)| *d == f_degree && checksum == *cksum)
+                    .any(|(_, cksum)| checksum == *cksum)
                 {
-                    tracing::error!("Checksum failed, removing {}", src.display());
-                    fs::remove_file(src)?;
-                    return Err(anyhow!("Checksum failed!"));
+                    // recorded against the latest accepted file
+                    let (_, expected) = ETH4844_CHECKSUMS[ETH4844_CHECKSUMS.len() - 1];
+                    quarantine(&src, &read_meta, &expected, &checksum)?;
+                    return Err(Error::ChecksumMismatch { path: src });
                 }
 
-                let mut srs = kzg10::UniversalParams::<Bn254>::deserialize_uncompressed_unchecked(
-                    &bytes[..],
-                )?;
+                TrustedSetup::from_txt(&bytes[..])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        kzg10::{bn254::aztec::*, load_params_prefix_with, load_params_with},
+        *,
+    };
+    use crate::{
+        ceremony::Ceremony,
+        config::Decompression,
+        constants::{
+            AZTEC20_CHECKSUMS, AZTEC20_CHUNK_ROOTS, AZTEC20_COMPRESSED_CHECKSUMS,
+            AZTEC20_COMPRESSED_CHUNK_ROOTS,
+        },
+        kzg10::aztec20::Aztec20,
+        manifest::AZTEC20,
+    };
+    use ark_bn254::Bn254;
 
-                // trim the srs to fit the actual requested degree
-                srs.powers_of_g.truncate(degree + 1);
-                Ok(srs)
+    #[test]
+    fn test_mapped_srs() -> Result<()> {
+        let src = PathBuf::from("data/aztec20/kzg10-aztec20-srs-1024.bin");
+        let pp = load_aztec_srs(1024, src.clone())?;
+        let mapped = map_aztec_srs(src.clone())?;
+        assert_eq!(mapped.max_degree(), 1024);
+        assert_eq!(mapped.powers_of_g(0..1025)?, pp.powers_of_g);
+        assert_eq!(mapped.powers_of_g(100..200)?, pp.powers_of_g[100..200]);
+        assert!(mapped.powers_of_g(1000..1026).is_err());
+        assert_eq!((mapped.h(), mapped.beta_h()), (pp.h, pp.beta_h));
+        let trimmed = mapped.universal_params(20)?;
+        assert_eq!(trimmed.powers_of_g, pp.powers_of_g[..21]);
+
+        // tampered files are rejected, but left in place
+        let dir = tempfile::tempdir()?;
+        let tampered = dir.path().join("kzg10-aztec20-srs-1024.bin");
+        let mut bytes = fs::read(&src)?;
+        bytes[100] ^= 1;
+        fs::write(&tampered, &bytes)?;
+        assert!(matches!(
+            map_aztec_srs(tampered.clone()),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(tampered.exists());
+        // without checksum, the layout is checked on opening and the points
+        // as they are decoded
+        let unchecked = MappedSrs::<Bn254>::open(&tampered, None)?;
+        assert!(matches!(
+            unchecked.powers_of_g(0..10),
+            Err(Error::Serialization(_))
+        ));
+        assert_eq!(unchecked.powers_of_g(2..10)?, pp.powers_of_g[2..10]);
+        assert!(unchecked.universal_params(20).is_err());
+        fs::write(&tampered, &bytes[..bytes.len() - 1])?;
+        assert!(MappedSrs::<Bn254>::open(&tampered, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_srs_header() -> Result<()> {
+        let legacy = PathBuf::from("data/aztec20/kzg10-aztec20-srs-1024.bin");
+        let mut pp = load_aztec_srs(1024, legacy.clone())?;
+        pp.powers_of_g.truncate(101);
+
+        // legacy files have no header
+        assert_eq!(SrsHeader::read(&legacy)?, None);
+        let (header, loaded) = load_srs::<Bn254>(&legacy, false)?;
+        assert_eq!((header, loaded.powers_of_g.len()), (None, 1025));
+
+        let dir = tempfile::tempdir()?;
+        for encoding in [PointEncoding::Compressed, PointEncoding::Uncompressed] {
+            let dest = dir.path().join("kzg10-aztec20-srs-100.bin");
+            let header = SrsHeader::kzg10(CurveId::Bn254, AZTEC20, encoding)
+                .source_hashes([[1u8; 32], [2u8; 32]]);
+            store_srs(&pp, header, &dest)?;
+
+            let header = SrsHeader::read(&dest)?.unwrap();
+            assert_eq!((header.degree, header.num_g2), (100, 2));
+            assert_eq!(header.encoding, encoding);
+            assert_eq!(header.source_hashes[1], hex::encode([2u8; 32]));
+            let (read, loaded) = load_srs::<Bn254>(&dest, true)?;
+            assert_eq!((read, loaded), (Some(header), pp.clone()));
+            // the degree is taken from the header, not the filename
+            let renamed = dir.path().join("srs.bin");
+            fs::rename(&dest, &renamed)?;
+            assert_eq!(
+                load_params_with::<Aztec20>(
+                    50,
+                    renamed.clone(),
+                    &[],
+                    TrustPolicy::Custom,
+                    Decompression::Unchecked,
+                    false,
+                )?
+                .powers_of_g,
+                pp.powers_of_g[..51]
+            );
+        }
+
+        // wrong curve, corrupted payload and unknown versions are rejected
+        let bytes = encode_srs(
+            &pp,
+            SrsHeader::kzg10(CurveId::Bn254, AZTEC20, PointEncoding::Compressed),
+        )?;
+        assert!(decode_srs::<ark_bls12_381::Bls12_381>(&bytes, false).is_err());
+        let mut tampered = bytes.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decode_srs::<Bn254>(&tampered, false).is_err());
+        let mut tampered = bytes.clone();
+        tampered[8] = 2;
+        assert!(SrsHeader::split(&tampered).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compressed_srs() -> Result<()> {
+        let src = PathBuf::from("data/aztec20/kzg10-aztec20-srs-1024.bin");
+        let pp = load_aztec_srs(1024, src.clone())?;
+        let dir = tempfile::tempdir()?;
+        let dest = compressed_path(Some(dir.path().to_path_buf()), 1024)?;
+        fs::create_dir_all(dest.parent().unwrap())?;
+        compress_aztec_srs(src.clone(), dest.clone())?;
+        assert!(compress_aztec_srs(dest.clone(), dir.path().join("again.bin")).is_err());
+
+        // every pinned file is reproducible from a released one of the same
+        // degree, and half its size
+        for (degree, checksum) in AZTEC20_COMPRESSED_CHECKSUMS {
+            assert!(AZTEC20_CHECKSUMS.iter().any(|(d, _)| *d == degree));
+            let released = default_path(None, degree)?;
+            if !released.exists() {
+                continue;
             }
+            let dest = compressed_path(Some(dir.path().to_path_buf()), degree)?;
+            compress_aztec_srs(released.clone(), dest.clone())?;
+            let bytes = fs::read(&dest)?;
+            assert_eq!(Sha256::digest(&bytes)[..], checksum);
+            assert!(bytes.len() < fs::read(&released)?.len() / 2 + 1024);
+        }
+
+        // the first load decodes the whole file, later ones only the prefix
+        for decompression in [Decompression::Checked, Decompression::Unchecked] {
+            let loaded = load_params_prefix_with::<Aztec20>(
+                100,
+                dest.clone(),
+                &Aztec20::checksums(),
+                &Aztec20::chunk_roots(),
+                TrustPolicy::Pinned,
+                decompression,
+                true,
+            )?;
+            assert_eq!(loaded.powers_of_g, pp.powers_of_g[..101]);
+            assert_eq!((loaded.h, loaded.beta_h), (pp.h, pp.beta_h));
+        }
+        let index = ChunkIndex::read(&ChunkIndex::sidecar_path(&dest))?;
+        assert_eq!(
+            (index.file_checksum, index.root),
+            AZTEC20_COMPRESSED_CHUNK_ROOTS[0]
+        );
+        assert_eq!(load_aztec_srs(1024, dest.clone())?, pp);
+        // can't be mapped
+        assert!(MappedSrs::<Bn254>::open(&dest, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_prefix() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let src = dir.path().join("kzg10-aztec20-srs-1024.bin");
+        fs::copy("data/aztec20/kzg10-aztec20-srs-1024.bin", &src)?;
+        let sidecar = ChunkIndex::sidecar_path(&src);
+        let load = |degree, src: &PathBuf, chunk_roots: &[([u8; 32], [u8; 32])]| {
+            load_params_prefix_with::<Aztec20>(
+                degree,
+                src.clone(),
+                &Aztec20::checksums(),
+                chunk_roots,
+                TrustPolicy::Pinned,
+                Decompression::Unchecked,
+                true,
+            )
+        };
+
+        // files supplied by the caller are never indexed, cached ones are
+        let pp = load_aztec_srs_prefix(1024, src.clone())?;
+        assert!(!sidecar.exists());
+        assert_eq!(load(1024, &src, &Aztec20::chunk_roots())?, pp);
+        assert_eq!(ChunkIndex::read(&sidecar)?.root, AZTEC20_CHUNK_ROOTS[0].1);
+        assert_eq!(load_aztec_srs_prefix(1024, src.clone())?, pp);
+
+        // smaller chunks, so that a prefix doesn't span the whole file, with
+        // their root pinned
+        let mut bytes = fs::read(&src)?;
+        let index = ChunkIndex::build(&bytes, 4096);
+        index.write(&sidecar)?;
+        let chunk_roots = [(index.file_checksum, index.root)];
+        let prefix = load(100, &src, &chunk_roots)?;
+        assert_eq!(prefix.powers_of_g, pp.powers_of_g[..101]);
+        assert_eq!((prefix.h, prefix.beta_h), (pp.h, pp.beta_h));
+        assert!(matches!(
+            load(1025, &src, &chunk_roots),
+            Err(Error::DegreeOutOfRange {
+                degree: 1025,
+                max: 1024
+            })
+        ));
+
+        // chunks beyond the prefix are neither read nor verified
+        bytes[8 + 900 * 64] ^= 1;
+        fs::write(&src, &bytes)?;
+        assert_eq!(load(100, &src, &chunk_roots)?, prefix);
+        // and the corrupted file is then quarantined
+        assert!(matches!(
+            load(900, &src, &chunk_roots),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(!src.exists() && !sidecar.exists());
+
+        // an unpinned index forged along with the file isn't trusted
+        let forged = dir.path().join("forged").join("kzg10-aztec20-srs-1024.bin");
+        fs::create_dir_all(forged.parent().unwrap())?;
+        fs::write(&forged, &bytes)?;
+        ChunkIndex::build(&bytes, 4096).write(&ChunkIndex::sidecar_path(&forged))?;
+        assert!(matches!(
+            load(100, &forged, &chunk_roots),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+
+        // a stale index is rebuilt
+        let restored = dir.path().join("restored.bin");
+        fs::copy("data/aztec20/kzg10-aztec20-srs-1024.bin", &restored)?;
+        let restored_sidecar = ChunkIndex::sidecar_path(&restored);
+        ChunkIndex::build(&bytes, 4096).write(&restored_sidecar)?;
+        assert_eq!(load(100, &restored, &chunk_roots)?, prefix);
+        let index = ChunkIndex::read(&restored_sidecar)?;
+        assert_eq!((index.file_checksum, index.root), AZTEC20_CHUNK_ROOTS[0]);
+
+        // the index itself is checked
+        let mut index = fs::read(&restored_sidecar)?;
+        *index.last_mut().unwrap() ^= 1;
+        fs::write(&restored_sidecar, &index)?;
+        assert!(ChunkIndex::read(&restored_sidecar).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_quarantine() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let src = dir.path().join("kzg10-aztec20-srs-1024.bin");
+        let mut bytes = fs::read("data/aztec20/kzg10-aztec20-srs-1024.bin")?;
+        let pp = load_aztec_srs(1024, "data/aztec20/kzg10-aztec20-srs-1024.bin".into())?;
+
+        // mismatching files are moved aside with a record of both digests
+        bytes[100] ^= 1;
+        fs::write(&src, &bytes)?;
+        assert!(matches!(
+            load_aztec_srs(1024, src.clone()),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(!src.exists());
+        let quarantined: Vec<_> = fs::read_dir(QuarantineRecord::quarantine_dir(&src))?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(quarantined.len(), 2);
+        let moved = quarantined
+            .iter()
+            .find(|p| p.extension() != Some("json".as_ref()));
+        assert_eq!(fs::read(moved.unwrap())?, bytes);
+        let record: QuarantineRecord =
+            serde_json::from_slice(&fs::read(QuarantineRecord::sidecar_path(moved.unwrap()))?)
+                .unwrap();
+        assert_eq!(record.source, src);
+        assert_eq!(record.expected, hex::encode(AZTEC20_CHECKSUMS[0].1));
+        assert_eq!(record.actual, hex::encode(Sha256::digest(&bytes)));
+
+        // so are indexed files corrupted since, on the prefix path, without
+        // clobbering earlier quarantines of the same entry
+        let load_cached = |src: &PathBuf| {
+            load_params_prefix_with::<Aztec20>(
+                100,
+                src.clone(),
+                &Aztec20::checksums(),
+                &Aztec20::chunk_roots(),
+                TrustPolicy::Pinned,
+                Decompression::Unchecked,
+                true,
+            )
+        };
+        for _ in 0..2 {
+            fs::copy("data/aztec20/kzg10-aztec20-srs-1024.bin", &src)?;
+            load_cached(&src)?;
+            fs::write(&src, &bytes)?;
+            assert!(matches!(
+                load_cached(&src),
+                Err(Error::ChecksumMismatch { .. })
+            ));
+            assert!(!src.exists() && !ChunkIndex::sidecar_path(&src).exists());
         }
+        // each with its index and record
+        assert_eq!(
+            fs::read_dir(QuarantineRecord::quarantine_dir(&src))?.count(),
+            8
+        );
+
+        // custom files are only accepted if trusted, and are then checked
+        let custom = dir.path().join("kzg10-aztec20-srs-100.bin");
+        let mut trimmed = pp.clone();
+        trimmed.powers_of_g.truncate(101);
+        store_data(trimmed.clone(), custom.clone())?;
+        assert!(matches!(
+            load_aztec_srs(100, custom.clone()),
+            Err(Error::UntrustedFile { .. })
+        ));
+        assert!(custom.exists());
+        let loaded = load_params_prefix_with::<Aztec20>(
+            100,
+            custom.clone(),
+            &AZTEC20_CHECKSUMS,
+            &AZTEC20_CHUNK_ROOTS,
+            TrustPolicy::Custom,
+            Decompression::Unchecked,
+            true,
+        )?;
+        assert_eq!(loaded, trimmed);
+        assert!(ChunkIndex::sidecar_path(&custom).exists());
+        assert!(matches!(
+            load_aztec_srs_prefix(100, custom.clone()),
+            Err(Error::UntrustedFile { .. })
+        ));
+
+        let invalid = dir.path().join("kzg10-aztec20-srs-101.bin");
+        let mut bytes = fs::read(&custom)?;
+        bytes[8 + 5 * 64] ^= 1;
+        fs::write(&invalid, &bytes)?;
+        assert!(load_params_with::<Aztec20>(
+            100,
+            invalid.clone(),
+            &AZTEC20_CHECKSUMS,
+            TrustPolicy::Custom,
+            Decompression::Unchecked,
+            false,
+        )
+        .is_err());
+        assert!(invalid.exists());
+        Ok(())
     }
 }
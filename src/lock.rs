@@ -0,0 +1,56 @@
+//! Cross-process advisory locks on cache entries, so that processes sharing a
+//! cache (e.g. test binaries started together) download each file only once,
+//! and never remove a file another one is writing.
+//!
+//! # Note
+//! The lock of `entry` is held on a separate `<entry>.lock` file, which is never
+//! removed: otherwise two processes could hold the "same" lock on two different
+//! files. Locks are advisory, thus only coordinate users of this module.
+
+use alloc::borrow::ToOwned;
+use std::{
+    fs::{create_dir_all, File, OpenOptions, TryLockError},
+    path::{Path, PathBuf},
+};
+
+use crate::errors::{Error, Result};
+
+/// Exclusive lock on a cache entry, released on drop.
+#[derive(Debug)]
+pub struct CacheLock {
+    // the lock is released when the file is closed
+    _file: File,
+}
+
+impl CacheLock {
+    /// Path of the lock file of `entry`
+    pub fn lock_path(entry: &Path) -> PathBuf {
+        let mut path = entry.as_os_str().to_owned();
+        path.push(".lock");
+        PathBuf::from(path)
+    }
+
+    /// Block until the exclusive lock of `entry` is acquired, creating its
+    /// parent directory if needed.
+    pub fn exclusive(entry: &Path) -> Result<Self> {
+        if let Some(parent) = entry.parent() {
+            create_dir_all(parent).map_err(|e| Error::file(parent, e))?;
+        }
+        let path = Self::lock_path(entry);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| Error::file(&path, e))?;
+        match file.try_lock() {
+            Ok(()) => {},
+            Err(TryLockError::WouldBlock) => {
+                tracing::info!("Waiting for another process to release {}", path.display());
+                file.lock().map_err(|e| Error::file(&path, e))?;
+            },
+            Err(TryLockError::Error(e)) => return Err(Error::file(&path, e)),
+        }
+        Ok(Self { _file: file })
+    }
+}
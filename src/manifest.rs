@@ -0,0 +1,223 @@
+//! Versioned manifest of the pre-serialized parameter files of a release, so
+//! that new releases can be used without a code change.
+//!
+//! Every release hosts a `manifest.json` next to its artifacts, e.g.
+//!
+//! ```json
+//! {
+//!   "version": "0.2.0",
+//!   "artifacts": [
+//!     {
+//!       "ceremony": "aztec20",
+//!       "degree": 1024,
+//!       "name": "kzg10-aztec20-srs-1024.bin",
+//!       "sha256": "0e2a5fb1d9102ee5b06723472b23f4f29f938712251a7b5b75eed4df4049871c",
+//!       "chunk_root": "0e2a5fb1d9102ee5b06723472b23f4f29f938712251a7b5b75eed4df4049871c"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! # Note
+//! The manifest of [`RELEASE_VERSION`] is compiled in from the pinned artifacts
+//! of each [`Ceremony`]. The manifests of other releases are fetched from the
+//! configured mirrors, thus only as trustworthy as these mirrors.
+
+use ark_std::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+use crate::{
+    ceremony::Ceremony,
+    config::SrsConfig,
+    constants::RELEASE_VERSION,
+    errors::{Error, Result},
+    kzg10::{aleo::Aleo, aztec20::Aztec20, zcash_powersoftau::ZcashPowersOfTau},
+    load::PointEncoding,
+};
+
+/// Ceremony name of the Aztec ignition artifacts
+pub const AZTEC20: &str = "aztec20";
+
+/// Ceremony name of the Aleo universal setup artifacts
+pub const ALEO: &str = "aleo";
+
+/// Ceremony name of the Zcash Powers of Tau artifacts
+pub const ZCASH_POWERSOFTAU: &str = "zcash_powersoftau";
+
+/// A pre-serialized parameter file of a release
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    /// ceremony the parameters are taken from, e.g. [`AZTEC20`]
+    pub ceremony: String,
+    /// max degree supported by the parameters
+    pub degree: usize,
+    /// filename in the release
+    pub name: String,
+    /// sha256sum of the file
+    #[serde(with = "hex::serde")]
+    pub sha256: [u8; 32],
+    /// encoding of the points, uncompressed if not listed
+    #[serde(default)]
+    pub encoding: PointEncoding,
+    /// root of the [`ChunkIndex`](crate::load::ChunkIndex) of the file, if pinned, so that a prefix of
+    /// it is verified without hashing the whole file
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "hex_option")]
+    pub chunk_root: Option<[u8; 32]>,
+}
+
+// `hex::serde` for optional digests
+mod hex_option {
+    use ark_std::string::String;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(digest: &Option<[u8; 32]>, s: S) -> Result<S::Ok, S::Error> {
+        match digest {
+            Some(digest) => s.serialize_str(&hex::encode(digest)),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<[u8; 32]>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|digest| {
+                let mut buf = [0u8; 32];
+                hex::decode_to_slice(digest, &mut buf).map_err(D::Error::custom)?;
+                Ok(buf)
+            })
+            .transpose()
+    }
+}
+
+/// List of the artifacts of a release
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    /// release version, without the `v` prefix
+    pub version: String,
+    /// artifacts of the release
+    pub artifacts: Vec<Artifact>,
+}
+
+impl ArtifactManifest {
+    /// Filename of the manifest in every release
+    pub const FILENAME: &'static str = "manifest.json";
+
+    /// The manifest of [`RELEASE_VERSION`], built from the pinned artifacts of
+    /// each [`Ceremony`]
+    pub fn builtin() -> Self {
+        Self {
+            version: RELEASE_VERSION.to_string(),
+            artifacts: [
+                Aztec20::artifacts(),
+                ZcashPowersOfTau::artifacts(),
+                Aleo::artifacts(),
+            ]
+            .concat(),
+        }
+    }
+
+    /// Parse a manifest from its json encoding
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes)
+            .map_err(|e| Error::MalformedCache(format!("Invalid artifact manifest: {e}")))
+    }
+
+    /// Encode the manifest into json
+    pub fn to_json(&self) -> Vec<u8> {
+        // serializing plain strings and integers never fails
+        serde_json::to_vec_pretty(self).unwrap_or_default()
+    }
+
+    /// `(degree, sha256sum)` of all artifacts of `ceremony`, in any encoding
+    pub fn checksums(&self, ceremony: &str) -> Vec<(usize, [u8; 32])> {
+        self.artifacts
+            .iter()
+            .filter(|a| a.ceremony == ceremony)
+            .map(|a| (a.degree, a.sha256))
+            .collect()
+    }
+
+    /// `(sha256sum, chunk index root)` of the artifacts of `ceremony` pinning
+    /// their [`ChunkIndex`](crate::load::ChunkIndex)
+    pub fn chunk_roots(&self, ceremony: &str) -> Vec<([u8; 32], [u8; 32])> {
+        self.artifacts
+            .iter()
+            .filter(|a| a.ceremony == ceremony)
+            .filter_map(|a| a.chunk_root.map(|root| (a.sha256, root)))
+            .collect()
+    }
+
+    /// Path of the cached manifest of `version` under the cache root
+    pub fn cache_path(cfg: &SrsConfig) -> Result<PathBuf> {
+        let mut path = cfg.cache_root()?;
+        path.push("manifests");
+        path.push(format!("v{}.json", cfg.release_version));
+        Ok(path)
+    }
+
+    /// The manifest of the release configured in `cfg`: the builtin one for
+    /// [`RELEASE_VERSION`], otherwise the cached one, otherwise downloaded
+    /// from the mirrors and cached.
+    pub fn resolve(cfg: &SrsConfig) -> Result<Self> {
+        if cfg.release_version == RELEASE_VERSION {
+            return Ok(Self::builtin());
+        }
+        let path = Self::cache_path(cfg)?;
+        if !path.exists() {
+            if cfg.is_offline() {
+                let hint = format!(
+                    "download {} from a mirror to it, or run with network access once",
+                    Self::FILENAME
+                );
+                return Err(Error::not_cached(vec![path], hint));
+            }
+            cfg.downloader
+                .fetch_from_mirrors(&cfg.release_urls(Self::FILENAME), &path, None)?;
+        }
+        let manifest = Self::from_json(&fs::read(&path).map_err(|e| Error::file(&path, e))?)?;
+        if manifest.version != cfg.release_version {
+            return Err(Error::MalformedCache(format!(
+                "{} is the manifest of v{}, expect v{}",
+                path.display(),
+                manifest.version,
+                cfg.release_version
+            )));
+        }
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constants::{AZTEC20_CHECKSUMS, AZTEC20_CHUNK_ROOTS};
+
+    #[test]
+    fn test_manifest_json() -> Result<()> {
+        let manifest = ArtifactManifest::builtin();
+        assert_eq!(ArtifactManifest::from_json(&manifest.to_json())?, manifest);
+        assert_eq!(manifest.checksums(AZTEC20), AZTEC20_CHECKSUMS);
+        assert_eq!(manifest.chunk_roots(AZTEC20), AZTEC20_CHUNK_ROOTS);
+        // compressed files aren't published in the default release
+        assert!(manifest
+            .artifacts
+            .iter()
+            .all(|a| a.encoding == PointEncoding::Uncompressed));
+        assert!(manifest.checksums("eth4844").is_empty());
+        assert!(ArtifactManifest::from_json(br#"{"version": "0.2.0"}"#).is_err());
+
+        // chunk roots are optional
+        let json = br#"{"version": "0.2.0", "artifacts": [{"ceremony": "aztec20",
+            "degree": 1024, "name": "kzg10-aztec20-srs-1024.bin", "sha256":
+            "0e2a5fb1d9102ee5b06723472b23f4f29f938712251a7b5b75eed4df4049871c"}]}"#;
+        let manifest = ArtifactManifest::from_json(json)?;
+        assert_eq!(manifest.artifacts[0].chunk_root, None);
+        assert!(manifest.chunk_roots(AZTEC20).is_empty());
+        Ok(())
+    }
+}
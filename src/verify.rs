@@ -0,0 +1,529 @@
+//! Cryptographic consistency checks of loaded SRS, complementary to the
+//! checksum pinned for released parameter files: these checks apply to any SRS,
+//! including the ones parsed from raw transcripts or user-generated files.
+
+use alloc::{format, vec, vec::Vec};
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::{UniformRand, Zero};
+use ark_serialize::Valid;
+
+use crate::errors::{Error, Result};
+
+/// verifying KZG10 parameters
+pub mod kzg10 {
+    use super::*;
+    use ark_poly_commit::kzg10::UniversalParams;
+
+    /// Verify that `pp` is a well-formed KZG10 SRS: all points are in the
+    /// prime order subgroups, and `powers_of_g` are successive powers of the
+    /// same secret `beta` in `beta_h = beta.h`.
+    ///
+    /// With random `r_i`, the powers are checked all at once via
+    /// `e(Σ r_i.g_{i+1}, h) = e(Σ r_i.g_i, beta_h)`, which only passes for
+    /// inconsistent powers with negligible probability. The optional
+    /// `powers_of_gamma_g` and `neg_powers_of_h`, if present, are checked
+    /// against the same `beta` similarly.
+    pub fn verify_powers<E: Pairing>(pp: &UniversalParams<E>) -> Result<()> {
+        let g = match pp.powers_of_g.first() {
+            Some(g) if !g.is_zero() => *g,
+            _ => {
+                return Err(Error::InvalidSrs(
+                    "Missing or trivial generator of G1".into(),
+                ))
+            },
+        };
+        if pp.powers_of_g.len() < 2 {
+            return Err(Error::InvalidSrs(format!(
+                "Expect at least 2 powers of G1, got {}",
+                pp.powers_of_g.len()
+            )));
+        }
+        if pp.h.is_zero() || pp.beta_h.is_zero() || pp.h == pp.beta_h {
+            return Err(Error::InvalidSrs(
+                "Trivial G2 elements, beta is 0 or 1".into(),
+            ));
+        }
+
+        // subgroup checks, the points are usually deserialized unchecked
+        if let Some(i) = pp
+            .powers_of_g
+            .iter()
+            .chain(pp.powers_of_gamma_g.values())
+            .position(|p| !is_valid(p))
+        {
+            return Err(Error::InvalidPoint(format!("G1 point at position {i}")));
+        }
+        if let Some(i) = [pp.h, pp.beta_h]
+            .iter()
+            .chain(pp.neg_powers_of_h.values())
+            .position(|p| !is_valid(p))
+        {
+            return Err(Error::InvalidPoint(format!("G2 point at position {i}")));
+        }
+
+        if !successive_powers::<E>(&pp.powers_of_g, pp.h, pp.beta_h) {
+            return Err(Error::InvalidSrs(
+                "powers_of_g are not successive powers of beta".into(),
+            ));
+        }
+
+        // only consecutive entries can be checked against each other
+        let gamma_g: Vec<_> = pp.powers_of_gamma_g.iter().collect();
+        for run in gamma_g.chunk_by(|(i, _), (j, _)| **i + 1 == **j) {
+            let run: Vec<E::G1Affine> = run.iter().map(|(_, p)| **p).collect();
+            if run.len() > 1 && !successive_powers::<E>(&run, pp.h, pp.beta_h) {
+                return Err(Error::InvalidSrs(
+                    "powers_of_gamma_g are not successive powers of beta".into(),
+                ));
+            }
+        }
+
+        // e(g_i, beta^{-i}.h) = e(g, h) for all i
+        let (g1, g2): (Vec<E::G1Affine>, Vec<E::G2Affine>) = pp
+            .neg_powers_of_h
+            .iter()
+            .filter_map(|(i, neg_h)| pp.powers_of_g.get(*i).map(|g_i| (*g_i, *neg_h)))
+            .unzip();
+        if !g1.is_empty() {
+            let r = random_scalars::<E>(g1.len());
+            let mut lhs: Vec<E::G1> = g1.iter().zip(r.iter()).map(|(p, r)| *p * r).collect();
+            lhs.push(g * -r.iter().sum::<E::ScalarField>());
+            if !E::multi_pairing(lhs, g2.into_iter().chain([pp.h])).is_zero() {
+                return Err(Error::InvalidSrs(
+                    "neg_powers_of_h are not negative powers of beta".into(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `pp` and `other` are powers of the same secret, i.e. come from
+    /// the same ceremony (or one derived from it, e.g. halo2 params from
+    /// Perpetual Powers of Tau), even with different generators:
+    /// `e(pp.g_1, other.h) = e(pp.g_0, other.beta_h)`.
+    ///
+    /// Only the first powers are compared, check each SRS with
+    /// [`verify_powers()`] as well.
+    pub fn same_tau_as<E: Pairing>(pp: &UniversalParams<E>, other: &UniversalParams<E>) -> bool {
+        match pp.powers_of_g.get(..2) {
+            Some([g, tau_g]) => E::multi_pairing(
+                [tau_g.into_group(), -g.into_group()],
+                [other.h, other.beta_h],
+            )
+            .is_zero(),
+            _ => false,
+        }
+    }
+
+    // e(Σ r_i.p_{i+1}, h) = e(Σ r_i.p_i, beta_h)
+    fn successive_powers<E: Pairing>(
+        powers: &[E::G1Affine],
+        h: E::G2Affine,
+        beta_h: E::G2Affine,
+    ) -> bool {
+        let n = powers.len() - 1;
+        let r = random_scalars::<E>(n);
+        let shifted = E::G1::msm_unchecked(&powers[1..], &r);
+        let unshifted = E::G1::msm_unchecked(&powers[..n], &r);
+        E::multi_pairing([shifted, -unshifted], [h, beta_h]).is_zero()
+    }
+
+    pub(super) fn random_scalars<E: Pairing>(n: usize) -> Vec<E::ScalarField> {
+        let rng = &mut rand::thread_rng();
+        (0..n).map(|_| E::ScalarField::rand(rng)).collect()
+    }
+}
+
+/// verifying the raw transcripts of Aztec's ignition ceremony
+pub mod aztec20 {
+    use super::*;
+    use crate::kzg10::aztec20::{
+        bytes_to_g1_points, bytes_to_g2, TranscriptManifest, CHUNK_SIZE, G1_SIZE, G2_SIZE,
+    };
+    use alloc::string::String;
+    use ark_bn254::{Bn254, G1Affine, G1Projective, G2Affine};
+    use blake2::{Blake2b512, Digest};
+    use std::{
+        fs::File,
+        io::{BufReader, Read},
+        path::Path,
+    };
+
+    /// Verification result of a single `transcriptNN.dat`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TranscriptReport {
+        /// manifest parsed from the file header
+        pub manifest: TranscriptManifest,
+        /// the BLAKE2b checksum at the end of the file matches its content
+        pub checksum_valid: bool,
+        /// the manifest is consistent with the previous transcripts and the
+        /// G1 range continues right after theirs
+        pub manifest_valid: bool,
+        /// all points are valid and the G1 points are successive powers of
+        /// the same `x`
+        pub powers_valid: bool,
+        /// the first G1 point is the next power after the last G1 point of
+        /// the previous transcript (or after the generator for transcript 0)
+        pub boundary_valid: bool,
+        /// human readable description of every failed check
+        pub issues: Vec<String>,
+    }
+
+    /// Verification result of a whole ceremony
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct IgnitionReport {
+        /// report of each transcript file found, in order
+        pub transcripts: Vec<TranscriptReport>,
+        /// all `total_transcripts` files are present
+        pub complete: bool,
+        /// number of G1 points successfully verified, i.e. the maximum degree
+        /// that can be trusted from these transcripts
+        pub num_verified_g1_points: usize,
+    }
+
+    impl IgnitionReport {
+        /// Returns true if every transcript passes all checks.
+        /// Incomplete ceremonies may still be valid for a smaller degree, see
+        /// `complete` and `num_verified_g1_points`.
+        pub fn is_valid(&self) -> bool {
+            !self.transcripts.is_empty() && self.transcripts.iter().all(|t| t.issues.is_empty())
+        }
+    }
+
+    /// Verify the `transcriptNN.dat` files under `dir` following
+    /// <https://github.com/AztecProtocol/ignition-verification>:
+    /// - the checksum at the end of each file is the BLAKE2b hash of the rest
+    /// - the manifests agree with each other, transcripts are numbered in
+    ///   order and their G1 ranges are contiguous
+    /// - all points are on the curve and in the prime order subgroup
+    /// - the G1 points are successive powers `x^i.[1]` of the same `x` as the
+    ///   first G2 point `x.[1]_2` of transcript 0, including across file
+    ///   boundaries
+    ///
+    /// Transcripts are read from `transcript00.dat` until the first missing
+    /// file. Errors are only returned if nothing can be verified (e.g.
+    /// `transcript00.dat` is missing), otherwise failed checks are recorded
+    /// in the report.
+    pub fn verify_transcripts(dir: impl AsRef<Path>) -> Result<IgnitionReport> {
+        let path = |i: usize| dir.as_ref().join(format!("transcript{i:02}.dat"));
+        let mut f = File::open(path(0)).map_err(|e| Error::file(path(0), e))?;
+        let first = TranscriptManifest::read(&mut f).map_err(|_| {
+            Error::MalformedTranscript("transcript00.dat is too short for a manifest".into())
+        })?;
+        if first.num_g2_points == 0 {
+            return Err(Error::MalformedTranscript(
+                "transcript00.dat contains no G2 point".into(),
+            ));
+        }
+
+        let mut report = IgnitionReport {
+            transcripts: Vec::new(),
+            complete: false,
+            num_verified_g1_points: 0,
+        };
+        // the last G1 point of the previous transcript, starting from [1]
+        let mut prev = Some(G1Affine::generator());
+        let mut x_h = None;
+        let mut next_start = 0u32;
+        let mut verified = true;
+        for i in 0..first.total_transcripts as usize {
+            let Ok(f) = File::open(path(i)) else {
+                break;
+            };
+            let (t, last, g2) = verify_transcript(f, i, &first, next_start, prev, x_h)?;
+            x_h = x_h.or(g2);
+            verified &= t.issues.is_empty();
+            if verified {
+                report.num_verified_g1_points += t.manifest.num_g1_points as usize;
+            }
+            next_start = t.manifest.start_from + t.manifest.num_g1_points;
+            prev = last;
+            report.transcripts.push(t);
+        }
+        report.complete = report.transcripts.len() == first.total_transcripts as usize;
+        if let Some(last) = report
+            .transcripts
+            .last_mut()
+            .filter(|_| report.complete && next_start != first.total_g1_points)
+        {
+            last.manifest_valid = false;
+            last.issues.push(format!(
+                "transcripts contain {next_start} G1 points, expect {}",
+                first.total_g1_points
+            ));
+            report.num_verified_g1_points = report.num_verified_g1_points.min(next_start as usize);
+        }
+        Ok(report)
+    }
+
+    // Returns the report, the last G1 point and the first G2 point of the
+    // transcript. `x_h` is `None` when verifying transcript 0, in which case its
+    // own first G2 point is used.
+    fn verify_transcript(
+        f: File,
+        index: usize,
+        first: &TranscriptManifest,
+        expected_start: u32,
+        prev: Option<G1Affine>,
+        x_h: Option<G2Affine>,
+    ) -> Result<(TranscriptReport, Option<G1Affine>, Option<G2Affine>)> {
+        let file_size = f.metadata()?.len();
+        let mut r = BufReader::new(f);
+        let mut hasher = Blake2b512::new();
+        let mut issues = Vec::new();
+
+        let mut buf = [0u8; TranscriptManifest::SIZE];
+        r.read_exact(&mut buf)?;
+        hasher.update(buf);
+        let manifest = TranscriptManifest::from_bytes(&buf);
+
+        let mut manifest_issues = Vec::new();
+        if manifest.transcript_number as usize != index {
+            manifest_issues.push(format!(
+                "transcript number is {}, expect {index}",
+                manifest.transcript_number
+            ));
+        }
+        if (
+            manifest.total_transcripts,
+            manifest.total_g1_points,
+            manifest.total_g2_points,
+        ) != (
+            first.total_transcripts,
+            first.total_g1_points,
+            first.total_g2_points,
+        ) {
+            manifest_issues.push("totals differ from transcript 0".into());
+        }
+        if manifest.start_from != expected_start {
+            manifest_issues.push(format!(
+                "G1 points start from {}, expect {expected_start}",
+                manifest.start_from
+            ));
+        }
+        // every transcript extends the powers, an empty one has no first
+        // point to check against the previous transcript
+        if manifest.num_g1_points == 0 {
+            manifest_issues.push("transcript contains no G1 point".into());
+        }
+        let manifest_valid = manifest_issues.is_empty();
+        issues.extend(manifest_issues);
+        if file_size != manifest.file_size() {
+            issues.push(format!(
+                "file has {file_size} bytes, expect {} from manifest",
+                manifest.file_size()
+            ));
+            let report = TranscriptReport {
+                manifest,
+                checksum_valid: false,
+                manifest_valid,
+                powers_valid: false,
+                boundary_valid: false,
+                issues,
+            };
+            return Ok((report, None, None));
+        }
+
+        // the G2 points come after all G1 points, read the G1 points first
+        // and accumulate random linear combinations for the final check
+        let mut point_issue = None;
+        let mut first_g1 = None;
+        let mut last_g1: Option<G1Affine> = None;
+        let (mut shifted, mut unshifted) = (G1Projective::zero(), G1Projective::zero());
+        let mut bytes = vec![0u8; CHUNK_SIZE * G1_SIZE];
+        let mut remaining = manifest.num_g1_points as usize;
+        while remaining > 0 {
+            let n = remaining.min(CHUNK_SIZE);
+            let bytes = &mut bytes[..n * G1_SIZE];
+            r.read_exact(bytes)?;
+            hasher.update(&bytes);
+            remaining -= n;
+            if point_issue.is_some() {
+                continue;
+            }
+
+            let offset = manifest.num_g1_points as usize - remaining - n;
+            let points = match bytes_to_g1_points(bytes, offset) {
+                Ok(points) => points,
+                Err(e) => {
+                    point_issue = Some(format!("{e:#}"));
+                    continue;
+                },
+            };
+            // consecutive pairs within the file, including across chunks
+            let chain: Vec<G1Affine> = last_g1.iter().copied().chain(points).collect();
+            let r = kzg10::random_scalars::<Bn254>(chain.len() - 1);
+            shifted += G1Projective::msm_unchecked(&chain[1..], &r);
+            unshifted += G1Projective::msm_unchecked(&chain[..chain.len() - 1], &r);
+            first_g1 = first_g1.or(chain.first().copied());
+            last_g1 = chain.last().copied();
+        }
+
+        let mut g2_points = Vec::new();
+        let mut buf = [0u8; G2_SIZE];
+        for _ in 0..manifest.num_g2_points {
+            r.read_exact(&mut buf)?;
+            hasher.update(buf);
+            match bytes_to_g2(&buf) {
+                Ok(p) => g2_points.push(p),
+                Err(e) => point_issue = point_issue.or(Some(format!("{e}"))),
+            }
+        }
+
+        let mut checksum = [0u8; TranscriptManifest::CHECKSUM_SIZE];
+        r.read_exact(&mut checksum)?;
+        let checksum_valid = hasher.finalize()[..] == checksum[..];
+        if !checksum_valid {
+            issues.push("BLAKE2b checksum mismatch".into());
+        }
+
+        let x_h = x_h.or(g2_points.first().copied());
+        let h = G2Affine::generator();
+        let mut powers_valid = point_issue.is_none();
+        if let Some(e) = point_issue {
+            issues.push(e);
+        } else if let Some(x_h) = x_h {
+            if !Bn254::multi_pairing([shifted, -unshifted], [h, x_h]).is_zero() {
+                powers_valid = false;
+                issues.push("G1 points are not successive powers of x".into());
+            }
+        } else {
+            powers_valid = false;
+            issues.push("missing x.[1]_2 to check the powers against".into());
+        }
+
+        // e(first, h) = e(prev, x.h)
+        let boundary_valid = match (prev, first_g1, x_h) {
+            _ if !manifest_valid || !powers_valid => false,
+            (Some(prev), Some(first), Some(x_h)) => {
+                Bn254::multi_pairing([first, -prev], [h, x_h]).is_zero()
+            },
+            _ => false,
+        };
+        if powers_valid && manifest_valid && !boundary_valid {
+            issues.push("first G1 point doesn't follow the previous transcript".into());
+        }
+
+        let report = TranscriptReport {
+            manifest,
+            checksum_valid,
+            manifest_valid,
+            powers_valid,
+            boundary_valid,
+            issues,
+        };
+        Ok((report, last_g1.or(prev), g2_points.first().copied()))
+    }
+}
+
+// on curve and in the prime order subgroup
+fn is_valid<P: Valid>(p: &P) -> bool {
+    p.check().is_ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{aztec20::verify_transcripts, kzg10::verify_powers};
+    use crate::kzg10::aztec20::test::write_mock_transcripts;
+    use crate::load::kzg10::bn254::aztec::load_aztec_srs;
+    use anyhow::Result;
+    use ark_bn254::{Bn254, Fr};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::UniformRand;
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly_commit::kzg10::KZG10;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_verify_powers() -> Result<()> {
+        let pp = load_aztec_srs(
+            1024,
+            PathBuf::from("data/aztec20/kzg10-aztec20-srs-1024.bin"),
+        )?;
+        verify_powers(&pp)?;
+
+        // arkworks' simulated setup, including gamma_g and negative powers
+        let rng = &mut ark_std::test_rng();
+        let mut simulated = KZG10::<Bn254, DensePolynomial<Fr>>::setup(16, true, rng)?;
+        verify_powers(&simulated)?;
+
+        let mut bad = pp.clone();
+        bad.powers_of_g.swap(3, 4);
+        assert!(verify_powers(&bad).is_err());
+        let mut bad = pp.clone();
+        bad.powers_of_g[10] = (bad.powers_of_g[10] * Fr::from(2u64)).into_affine();
+        assert!(verify_powers(&bad).is_err());
+        let mut bad = pp;
+        bad.beta_h = (bad.beta_h + bad.h).into_affine();
+        assert!(verify_powers(&bad).is_err());
+
+        let p = simulated.neg_powers_of_h.get_mut(&3).unwrap();
+        *p = (*p + ark_bn254::G2Affine::generator()).into_affine();
+        assert!(verify_powers(&simulated).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_transcripts() -> Result<()> {
+        let tau = Fr::rand(&mut ark_std::test_rng());
+        let dir = tempfile::tempdir()?;
+        let sizes = [100, 100, 50];
+        write_mock_transcripts(dir.path(), tau, &sizes)?;
+
+        let report = verify_transcripts(dir.path())?;
+        assert!(report.is_valid() && report.complete, "{report:?}");
+        assert_eq!(report.num_verified_g1_points, 250);
+
+        // incomplete but valid ceremonies
+        std::fs::remove_file(dir.path().join("transcript02.dat"))?;
+        let report = verify_transcripts(dir.path())?;
+        assert!(report.is_valid() && !report.complete);
+        assert_eq!(report.num_verified_g1_points, 200);
+
+        // tampering the checksum, the points, or the manifest
+        let path = dir.path().join("transcript01.dat");
+        let original = std::fs::read(&path)?;
+        let mut bytes = original.clone();
+        *bytes.last_mut().unwrap() ^= 1;
+        std::fs::write(&path, &bytes)?;
+        let t = &verify_transcripts(dir.path())?.transcripts[1];
+        assert!(!t.checksum_valid && t.powers_valid && t.boundary_valid);
+
+        // swap two G1 points
+        let mut bytes = original.clone();
+        let (a, b) = (28 + 10 * 64, 28 + 11 * 64);
+        let p = bytes[a..b].to_vec();
+        bytes.copy_within(b..b + 64, a);
+        bytes[b..b + 64].copy_from_slice(&p);
+        std::fs::write(&path, &bytes)?;
+        let report = verify_transcripts(dir.path())?;
+        assert!(!report.transcripts[1].powers_valid && !report.is_valid());
+        assert_eq!(report.num_verified_g1_points, 100);
+
+        // transcript 1 from another ceremony
+        write_mock_transcripts(dir.path(), tau + Fr::from(1u64), &sizes)?;
+        let other = std::fs::read(&path)?;
+        write_mock_transcripts(dir.path(), tau, &sizes)?;
+        std::fs::write(&path, other)?;
+        let t = &verify_transcripts(dir.path())?.transcripts[1];
+        assert!(t.checksum_valid && !t.powers_valid && !t.boundary_valid);
+
+        // an empty transcript is a manifest error, not a broken boundary
+        write_mock_transcripts(dir.path(), tau, &[100, 0, 50])?;
+        let report = verify_transcripts(dir.path())?;
+        let t = &report.transcripts[1];
+        assert!(!t.manifest_valid && t.checksum_valid && !t.boundary_valid);
+        assert_eq!(t.issues, ["transcript contains no G1 point"]);
+        assert!(report.transcripts[2].boundary_valid);
+        assert_eq!(report.num_verified_g1_points, 100);
+
+        // non-contiguous G1 ranges
+        write_mock_transcripts(dir.path(), tau, &sizes)?;
+        let mut bytes = original;
+        bytes[27] += 1; // start_from
+        std::fs::write(&path, bytes)?;
+        let t = &verify_transcripts(dir.path())?.transcripts[1];
+        assert!(!t.manifest_valid && !t.checksum_valid);
+        Ok(())
+    }
+}